@@ -10,9 +10,14 @@
 //!
 //! |   Provider    |                      Products                      |
 //! | :-----------: | :------------------------------------------------: |
-//! | Tencent Cloud |         Cloud Virtual Machine, Lighthouse          |
-//! |    Aliyun     | Elastic Compute Service, Simple Application Server |
+//! | Tencent Cloud | Cloud Virtual Machine, Lighthouse, IP Address Template, TencentDB for MySQL, DNSPod |
+//! |    Aliyun     | Elastic Compute Service, Simple Application Server, Prefix List, RDS, Redis/Tair, Cloud Firewall, Alibaba Cloud DNS |
 //! |    Rainyun    |                     RCS                            |
+//! |  Cloudflare   |          IP Lists, IP Access Rules, DNS           |
+//! | DigitalOcean  |                  Cloud Firewalls                   |
+//! |    Hetzner    |                     Firewalls                      |
+//! |     Vultr     |                  Firewall Groups                   |
+//! |  Kubernetes   |    NetworkPolicy, Ingress allowlist annotation     |
 
 pub mod client;
 mod error;
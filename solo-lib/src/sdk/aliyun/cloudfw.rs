@@ -0,0 +1,235 @@
+//! # Aliyun Cloud Firewall
+//!
+//! Begin with the [`go`] function
+//!
+//! Cloud Firewall controls internet-facing access through address books,
+//! the IP addresses of an address book are replaced with the current
+//! address.
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, Empty, MachineType, Secret, parse_response,
+    request_builder,
+};
+use crate::{error::SdkError, util::family_addresses};
+
+/// Cloud Firewall address book
+///
+/// The `id` is either the UUID or the name of the address book.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressBook {
+    pub id: String,
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeAddressBookResponse {
+    #[serde(rename = "Acls", default)]
+    pub acls: Vec<AddressBookInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressBookInfo {
+    #[serde(rename = "GroupUuid")]
+    pub group_uuid: String,
+    #[serde(rename = "GroupName")]
+    pub group_name: String,
+    #[serde(rename = "GroupType")]
+    pub group_type: String,
+    #[serde(rename = "Description", default)]
+    pub description: String,
+    #[serde(rename = "AddressList", default)]
+    pub address_list: Vec<String>,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the address book.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::aliyun::{
+///     Secret,
+///     cloudfw::{AddressBook, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         secret_id: "secret_id".to_string(),
+///         secret_key: "secret_key".to_string(),
+///     };
+///     let address_book = AddressBook {
+///         id: "address_book_uuid_or_name".to_string(),
+///         region: "cn-hangzhou".to_string(),
+///     };
+///     let _result = go(
+///         &client,
+///         &address_book,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["203.0.113.0/24".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    address_book: impl Borrow<AddressBook>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+) -> Result<()> {
+    let info =
+        find_address_book(client, address_book.borrow(), secret.borrow())
+            .await?;
+    let (info, require_update) =
+        compare_rules(&info, current_ipv4, current_ipv6, extra_addresses);
+    if require_update {
+        modify_rules(client, address_book.borrow(), secret.borrow(), &info)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Find the address book by its UUID or name.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn find_address_book(
+    client: &Client,
+    address_book: &AddressBook,
+    secret: &Secret,
+) -> Result<AddressBookInfo> {
+    let mut page = 1;
+    loop {
+        let response = list_rules(client, address_book, secret, page).await?;
+        let response = response.response;
+        let page_len = response.acls.len();
+        if let Some(info) = response.acls.into_iter().find(|info| {
+            info.group_uuid == address_book.id
+                || info.group_name == address_book.id
+        }) {
+            return Ok(info);
+        }
+        if page_len < 50 {
+            break;
+        }
+        page += 1;
+    }
+
+    Err(SdkError {
+        request_id: String::new(),
+        code: "ResourceNotFound".to_string(),
+        message: format!("Address book {} not found", address_book.id),
+    }
+    .into())
+}
+
+/// ### SDK Implementation DescribeAddressBook
+///
+/// Lists one page of the IP address books, `page` starts at 1.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    address_book: &AddressBook,
+    secret: &Secret,
+    page: usize,
+) -> Result<CommonResponse<DescribeAddressBookResponse>> {
+    let page = page.to_string();
+    let params = vec![
+        ("CurrentPage", page.as_str()),
+        ("PageSize", "50"),
+        ("Query", address_book.id.as_str()),
+    ];
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Cloudfw,
+        region_id: &address_book.region,
+        action: "DescribeAddressBook",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeAddressBookResponse>>(&result)
+}
+
+/// ### SDK Process CompareAddressBook
+///
+/// IPv6 address books get the current IPv6 address, every other address
+/// book gets the current IPv4 address. The `extra_addresses` of the same
+/// family are kept next to it.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    info: &AddressBookInfo,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+) -> (AddressBookInfo, bool) {
+    let is_ipv6 = info.group_type.eq_ignore_ascii_case("ipv6");
+    let current = if is_ipv6 { current_ipv6 } else { current_ipv4 };
+    // Address books hold CIDR blocks
+    let addresses = family_addresses(current, extra_addresses, is_ipv6, true);
+    let old: HashSet<&String> = info.address_list.iter().collect();
+    let new: HashSet<&String> = addresses.iter().collect();
+    if addresses.is_empty() || old == new {
+        return (info.clone(), false);
+    }
+
+    let modified = AddressBookInfo {
+        address_list: addresses,
+        ..info.clone()
+    };
+    (modified, true)
+}
+
+/// ### SDK Implementation ModifyAddressBook
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    address_book: &AddressBook,
+    secret: &Secret,
+    info: &AddressBookInfo,
+) -> Result<CommonResponse<Empty>> {
+    let address_list = info.address_list.join(",");
+    let params = vec![
+        ("GroupUuid", info.group_uuid.as_str()),
+        ("GroupName", info.group_name.as_str()),
+        ("Description", info.description.as_str()),
+        ("AddressList", address_list.as_str()),
+    ];
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Cloudfw,
+        region_id: &address_book.region,
+        action: "ModifyAddressBook",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<Empty>>(&result)
+}
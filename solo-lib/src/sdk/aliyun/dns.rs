@@ -0,0 +1,237 @@
+//! # Aliyun DNS
+//!
+//! Begin with the [`go`] function
+//!
+//! `A` records are set to the current IPv4 address and `AAAA` records to
+//! the current IPv6 address.
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, MachineType, Secret, parse_response,
+    request_builder,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Domain {
+    pub name: String,
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeDomainRecordsResponse {
+    #[serde(rename = "DomainRecords")]
+    pub domain_records: DescribeDomainRecordsResponseRecords,
+    #[serde(rename = "TotalCount", default)]
+    pub total_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeDomainRecordsResponseRecords {
+    #[serde(rename = "Record", default)]
+    pub record: Vec<Record>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(rename = "RecordId")]
+    pub record_id: String,
+    /// Host record, `@` for the domain itself
+    #[serde(rename = "RR")]
+    pub rr: String,
+    #[serde(rename = "Type")]
+    pub record_type: String,
+    #[serde(rename = "Value")]
+    pub value: String,
+    #[serde(rename = "TTL", default)]
+    pub ttl: u32,
+    #[serde(rename = "Line", default)]
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateDomainRecordResponse {
+    #[serde(rename = "RecordId")]
+    pub record_id: String,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the DNS records.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::aliyun::{
+///     Secret,
+///     dns::{Domain, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         secret_id: "secret_id".to_string(),
+///         secret_key: "secret_key".to_string(),
+///     };
+///     let domain = Domain {
+///         name: "example.com".to_string(),
+///         region: String::new(),
+///     };
+///     let _result = go(
+///         &client,
+///         &domain,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["home".to_string(), "@".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    domain: impl Borrow<Domain>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_records: &[String],
+) -> Result<()> {
+    let records = list_rules(client, domain.borrow(), secret.borrow()).await?;
+    let (records, require_update) =
+        compare_rules(&records, current_ipv4, current_ipv6, matched_records);
+    if require_update {
+        modify_rules(client, domain.borrow(), secret.borrow(), &records)
+            .await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation DescribeDomainRecords
+///
+/// Fetches every page of the records.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    domain: &Domain,
+    secret: &Secret,
+) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+    let mut page = 1;
+    loop {
+        let page_str = page.to_string();
+        let params = vec![
+            ("DomainName", domain.name.as_str()),
+            ("PageNumber", page_str.as_str()),
+            ("PageSize", "500"),
+        ];
+        let basic_request = BasicRequest {
+            machine_type: MachineType::Dns,
+            region_id: &domain.region,
+            action: "DescribeDomainRecords",
+            secret,
+            params: &params,
+            body: "",
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        let response = parse_response::<
+            CommonResponse<DescribeDomainRecordsResponse>,
+        >(&result)?
+        .response;
+        let page_len = response.domain_records.record.len();
+        records.extend(response.domain_records.record);
+        if page_len == 0 || records.len() >= response.total_count {
+            break;
+        }
+        page += 1;
+    }
+    Ok(records)
+}
+
+/// ### SDK Process CompareDomainRecords
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    records: &[Record],
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_records: &[String],
+) -> (Vec<Record>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_records.iter().map(|s| s.as_str()).collect();
+
+    let mut records_to_be_modified = Vec::new();
+    for record in records {
+        if !matched_set.contains(record.rr.as_str()) {
+            continue;
+        }
+        let current = match record.record_type.as_str() {
+            "A" => current_ipv4,
+            "AAAA" => current_ipv6,
+            _ => continue,
+        };
+        if !current.is_empty() && record.value != current {
+            records_to_be_modified.push(Record {
+                value: current.to_string(),
+                ..record.clone()
+            });
+        }
+    }
+
+    let require_update = !records_to_be_modified.is_empty();
+    (records_to_be_modified, require_update)
+}
+
+/// ### SDK Implementation UpdateDomainRecord
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    domain: &Domain,
+    secret: &Secret,
+    records: &[Record],
+) -> Result<()> {
+    for record in records {
+        let ttl = record.ttl.to_string();
+        let mut params = vec![
+            ("RecordId", record.record_id.as_str()),
+            ("RR", record.rr.as_str()),
+            ("Type", record.record_type.as_str()),
+            ("Value", record.value.as_str()),
+        ];
+        if record.ttl != 0 {
+            params.push(("TTL", ttl.as_str()));
+        }
+        if !record.line.is_empty() {
+            params.push(("Line", record.line.as_str()));
+        }
+        let basic_request = BasicRequest {
+            machine_type: MachineType::Dns,
+            region_id: &domain.region,
+            action: "UpdateDomainRecord",
+            secret,
+            params: &params,
+            body: "",
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        parse_response::<CommonResponse<UpdateDomainRecordResponse>>(&result)?;
+    }
+    Ok(())
+}
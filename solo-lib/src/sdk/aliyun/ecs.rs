@@ -20,6 +20,15 @@ pub struct SecurityGroup {
     pub region: String,
 }
 
+/// ECS instance
+///
+/// Used to look up the security groups attached to an instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Instance {
+    pub id: String,
+    pub region: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityGroupRule {
     #[serde(
@@ -146,6 +155,48 @@ pub struct DescribeSecurityGroupAttributeResponsePermissions {
     pub permission: Vec<SecurityGroupRule>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeInstanceAttributeResponse {
+    #[serde(rename = "InstanceId")]
+    pub instance_id: String,
+    #[serde(rename = "SecurityGroupIds")]
+    pub security_group_ids: DescribeInstanceAttributeResponseSecurityGroupIds,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeInstanceAttributeResponseSecurityGroupIds {
+    #[serde(rename = "SecurityGroupId", default)]
+    pub security_group_id: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeSecurityGroupsResponse {
+    #[serde(rename = "SecurityGroups")]
+    pub security_groups: DescribeSecurityGroupsResponseSecurityGroups,
+    #[serde(rename = "NextToken", default)]
+    pub next_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeSecurityGroupsResponseSecurityGroups {
+    #[serde(rename = "SecurityGroup", default)]
+    pub security_group: Vec<SecurityGroupInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityGroupInfo {
+    #[serde(rename = "SecurityGroupId")]
+    pub security_group_id: String,
+    #[serde(rename = "SecurityGroupName", default)]
+    pub security_group_name: String,
+}
+
+/// Returns whether the given ID is an ECS instance ID (`i-…`) rather than a
+/// security group ID (`sg-…`).
+pub fn is_instance_id(id: &str) -> bool {
+    id.starts_with("i-")
+}
+
 /// ### Solo GO! - Main function
 ///
 /// Start to modify the firewall rules.
@@ -254,6 +305,75 @@ pub async fn list_rules(
     )
 }
 
+/// ### SDK Implementation DescribeInstanceAttribute
+///
+/// Looks up the instance, including the IDs of its attached security
+/// groups.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn describe_instance(
+    client: &Client,
+    instance: &Instance,
+    secret: &Secret,
+) -> Result<CommonResponse<DescribeInstanceAttributeResponse>> {
+    let params = vec![("InstanceId", instance.id.as_str())];
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Ecs,
+        region_id: &instance.region,
+        action: "DescribeInstanceAttribute",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeInstanceAttributeResponse>>(&result)
+}
+
+/// ### SDK Implementation DescribeSecurityGroups
+///
+/// Lists one page of the security groups in a region whose tags match all
+/// of the given `(key, value)` pairs. Pass the `next_token` of the previous
+/// page to continue, or an empty string to start from the beginning.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_security_groups(
+    client: &Client,
+    region: &str,
+    secret: &Secret,
+    tags: &[(String, String)],
+    next_token: &str,
+) -> Result<CommonResponse<DescribeSecurityGroupsResponse>> {
+    let mut params = vec![("RegionId", region), ("MaxResults", "100")];
+    if !next_token.is_empty() {
+        params.push(("NextToken", next_token));
+    }
+    let keys = (1..=tags.len())
+        .map(|i| (format!("Tag.{i}.Key"), format!("Tag.{i}.Value")))
+        .collect::<Vec<_>>();
+    for ((key, value), (tag_key, tag_value)) in keys.iter().zip(tags) {
+        params.push((key.as_str(), tag_key.as_str()));
+        params.push((value.as_str(), tag_value.as_str()));
+    }
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Ecs,
+        region_id: region,
+        action: "DescribeSecurityGroups",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeSecurityGroupsResponse>>(&result)
+}
+
 /// ### SDK Process CompareSecurityGroupPolicies
 ///
 /// Note that this function is a single step of solo. Use it only if you
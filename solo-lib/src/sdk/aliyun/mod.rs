@@ -3,8 +3,18 @@
 //! Supports:
 //! - ECS [`ecs`]
 //! - SAS [`sas`]
+//! - Prefix List [`prefix_list`]
+//! - RDS [`rds`]
+//! - Redis/Tair [`redis`]
+//! - Cloud Firewall [`cloudfw`]
+//! - DNS [`dns`]
 
+pub mod cloudfw;
+pub mod dns;
 pub mod ecs;
+pub mod prefix_list;
+pub mod rds;
+pub mod redis;
 pub mod sas;
 mod util;
 pub use util::*;
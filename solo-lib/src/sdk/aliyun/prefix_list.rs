@@ -0,0 +1,315 @@
+//! # Aliyun Prefix List
+//!
+//! Begin with the [`go`] function
+//!
+//! A prefix list can be referenced by many ECS security groups, updating it
+//! once updates all of them.
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, Empty, MachineType, Secret, parse_response,
+    request_builder,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefixList {
+    pub id: String,
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PrefixListEntry {
+    #[serde(rename = "Cidr")]
+    pub cidr: String,
+    #[serde(rename = "Description", default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribePrefixListAttributesResponse {
+    #[serde(rename = "PrefixListId")]
+    pub prefix_list_id: String,
+    #[serde(rename = "PrefixListName", default)]
+    pub prefix_list_name: String,
+    #[serde(rename = "AddressFamily")]
+    pub address_family: String,
+    #[serde(rename = "Entries")]
+    pub entries: DescribePrefixListAttributesResponseEntries,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribePrefixListAttributesResponseEntries {
+    #[serde(rename = "Entry", default)]
+    pub entry: Vec<PrefixListEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribePrefixListAssociationsResponse {
+    #[serde(rename = "PrefixListAssociations")]
+    pub prefix_list_associations:
+        DescribePrefixListAssociationsResponseAssociations,
+    #[serde(rename = "NextToken", default)]
+    pub next_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribePrefixListAssociationsResponseAssociations {
+    #[serde(rename = "PrefixListAssociation", default)]
+    pub prefix_list_association: Vec<PrefixListAssociation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefixListAssociation {
+    #[serde(rename = "ResourceId")]
+    pub resource_id: String,
+    #[serde(rename = "ResourceType", default)]
+    pub resource_type: String,
+}
+
+/// Entries to be removed from and added to a prefix list
+#[derive(Debug, Clone, Default)]
+pub struct PrefixListChanges {
+    pub remove: Vec<PrefixListEntry>,
+    pub add: Vec<PrefixListEntry>,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the prefix list entries.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::aliyun::{
+///     Secret,
+///     prefix_list::{PrefixList, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         secret_id: "secret_id".to_string(),
+///         secret_key: "secret_key".to_string(),
+///     };
+///     let prefix_list = PrefixList {
+///         id: "prefix_list_id".to_string(),
+///         region: "prefix_list_region".to_string(),
+///     };
+///     let _result = go(
+///         &client,
+///         &prefix_list,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &[
+///             "entry_description_one".to_string(),
+///             "entry_description_two".to_string(),
+///         ],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    prefix_list: impl Borrow<PrefixList>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_descriptions: &[String],
+) -> Result<()> {
+    let response =
+        list_rules(client, prefix_list.borrow(), secret.borrow()).await?;
+    let (changes, require_update) = compare_rules(
+        &response.response,
+        current_ipv4,
+        current_ipv6,
+        matched_descriptions,
+    );
+    if require_update {
+        modify_rules(client, prefix_list.borrow(), secret.borrow(), &changes)
+            .await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation DescribePrefixListAttributes
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    prefix_list: &PrefixList,
+    secret: &Secret,
+) -> Result<CommonResponse<DescribePrefixListAttributesResponse>> {
+    let params = vec![
+        ("PrefixListId", prefix_list.id.as_str()),
+        ("RegionId", prefix_list.region.as_str()),
+    ];
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Ecs,
+        region_id: &prefix_list.region,
+        action: "DescribePrefixListAttributes",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribePrefixListAttributesResponse>>(
+        &result,
+    )
+}
+
+/// ### SDK Implementation DescribePrefixListAssociations
+///
+/// Lists one page of the resources, usually security groups, that reference
+/// the prefix list. Pass the `next_token` of the previous page to continue,
+/// or an empty string to start from the beginning.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_associations(
+    client: &Client,
+    prefix_list: &PrefixList,
+    secret: &Secret,
+    next_token: &str,
+) -> Result<CommonResponse<DescribePrefixListAssociationsResponse>> {
+    let mut params = vec![
+        ("PrefixListId", prefix_list.id.as_str()),
+        ("RegionId", prefix_list.region.as_str()),
+        ("MaxResults", "100"),
+    ];
+    if !next_token.is_empty() {
+        params.push(("NextToken", next_token));
+    }
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Ecs,
+        region_id: &prefix_list.region,
+        action: "DescribePrefixListAssociations",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribePrefixListAssociationsResponse>>(
+        &result,
+    )
+}
+
+/// ### SDK Process ComparePrefixListEntries
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    prefix_list: &DescribePrefixListAttributesResponse,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_descriptions: &[String],
+) -> (PrefixListChanges, bool) {
+    let matched_set: HashSet<&str> =
+        matched_descriptions.iter().map(|s| s.as_str()).collect();
+
+    let current = if prefix_list.address_family.eq_ignore_ascii_case("IPv6") {
+        to_cidr(current_ipv6, 128)
+    } else {
+        to_cidr(current_ipv4, 32)
+    };
+
+    let mut changes = PrefixListChanges::default();
+    if current.is_empty() {
+        return (changes, false);
+    }
+    // Matched entries collapse to the current address, which can be added
+    // only once and not at all if it is already listed
+    let mut added: HashSet<&str> = prefix_list
+        .entries
+        .entry
+        .iter()
+        .map(|entry| entry.cidr.as_str())
+        .filter(|cidr| *cidr == current)
+        .collect();
+    for entry in &prefix_list.entries.entry {
+        if matched_set.contains(entry.description.as_str())
+            && entry.cidr != current
+        {
+            changes.remove.push(entry.clone());
+            if added.insert(&current) {
+                changes.add.push(PrefixListEntry {
+                    cidr: current.clone(),
+                    ..entry.clone()
+                });
+            }
+        }
+    }
+
+    let require_update = !changes.remove.is_empty();
+    (changes, require_update)
+}
+
+/// ### SDK Implementation ModifyPrefixList
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    prefix_list: &PrefixList,
+    secret: &Secret,
+    changes: &PrefixListChanges,
+) -> Result<CommonResponse<Empty>> {
+    let mut pairs = Vec::new();
+    for (i, entry) in changes.add.iter().enumerate() {
+        pairs.push((format!("AddEntry.{}.Cidr", i + 1), entry.cidr.clone()));
+        if !entry.description.is_empty() {
+            pairs.push((
+                format!("AddEntry.{}.Description", i + 1),
+                entry.description.clone(),
+            ));
+        }
+    }
+    for (i, entry) in changes.remove.iter().enumerate() {
+        pairs.push((format!("RemoveEntry.{}.Cidr", i + 1), entry.cidr.clone()));
+    }
+
+    let mut params = vec![
+        ("PrefixListId", prefix_list.id.as_str()),
+        ("RegionId", prefix_list.region.as_str()),
+    ];
+    params.extend(pairs.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Ecs,
+        region_id: &prefix_list.region,
+        action: "ModifyPrefixList",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<Empty>>(&result)
+}
+
+/// Prefix list entries are CIDR blocks, plain addresses are turned into a
+/// single host block.
+fn to_cidr(address: &str, prefix_len: u8) -> String {
+    if address.is_empty() || address.contains('/') {
+        address.to_string()
+    } else {
+        format!("{address}/{prefix_len}")
+    }
+}
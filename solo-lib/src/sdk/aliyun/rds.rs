@@ -0,0 +1,218 @@
+//! # Aliyun RDS
+//!
+//! Begin with the [`go`] function
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, Empty, MachineType, Secret, parse_response,
+    request_builder,
+};
+use crate::util::family_addresses;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbInstance {
+    pub id: String,
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeDBInstanceIPArrayListResponse {
+    #[serde(rename = "Items")]
+    pub items: DescribeDBInstanceIPArrayListResponseItems,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeDBInstanceIPArrayListResponseItems {
+    #[serde(rename = "DBInstanceIPArray", default)]
+    pub db_instance_ip_array: Vec<DBInstanceIPArray>,
+}
+
+/// A whitelist group of an RDS instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DBInstanceIPArray {
+    #[serde(rename = "DBInstanceIPArrayName")]
+    pub db_instance_ip_array_name: String,
+    #[serde(rename = "DBInstanceIPArrayAttribute", default)]
+    pub db_instance_ip_array_attribute: String,
+    #[serde(rename = "SecurityIPType", default)]
+    pub security_ip_type: String,
+    #[serde(rename = "SecurityIPList", default)]
+    pub security_ip_list: String,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the whitelist groups of an RDS instance.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::aliyun::{
+///     Secret,
+///     rds::{DbInstance, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         secret_id: "secret_id".to_string(),
+///         secret_key: "secret_key".to_string(),
+///     };
+///     let instance = DbInstance {
+///         id: "db_instance_id".to_string(),
+///         region: "db_instance_region".to_string(),
+///     };
+///     let _result = go(
+///         &client,
+///         &instance,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["203.0.113.0/24".to_string()],
+///         &["whitelist_group_name".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    instance: impl Borrow<DbInstance>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_groups: &[String],
+) -> Result<()> {
+    let response =
+        list_rules(client, instance.borrow(), secret.borrow()).await?;
+    let (groups, require_update) = compare_rules(
+        &response.response,
+        current_ipv4,
+        current_ipv6,
+        extra_addresses,
+        matched_groups,
+    );
+    if require_update {
+        modify_rules(client, instance.borrow(), secret.borrow(), &groups)
+            .await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation DescribeDBInstanceIPArrayList
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    instance: &DbInstance,
+    secret: &Secret,
+) -> Result<CommonResponse<DescribeDBInstanceIPArrayListResponse>> {
+    let params = vec![("DBInstanceId", instance.id.as_str())];
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Rds,
+        region_id: &instance.region,
+        action: "DescribeDBInstanceIPArrayList",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeDBInstanceIPArrayListResponse>>(
+        &result,
+    )
+}
+
+/// ### SDK Process CompareWhitelistGroups
+///
+/// The groups whose name is in `matched_groups` are replaced with the
+/// current address and the `extra_addresses` of the same family, IPv6 groups
+/// get the IPv6 addresses.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    response: &DescribeDBInstanceIPArrayListResponse,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_groups: &[String],
+) -> (Vec<DBInstanceIPArray>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_groups.iter().map(|s| s.as_str()).collect();
+
+    let mut modified_groups = Vec::new();
+    for group in &response.items.db_instance_ip_array {
+        if !matched_set.contains(group.db_instance_ip_array_name.as_str()) {
+            continue;
+        }
+        let is_ipv6 = group.security_ip_type.eq_ignore_ascii_case("IPv6");
+        let current = if is_ipv6 { current_ipv6 } else { current_ipv4 };
+        let addresses =
+            family_addresses(current, extra_addresses, is_ipv6, false);
+        let old: HashSet<&str> =
+            group.security_ip_list.split(',').map(str::trim).collect();
+        let new: HashSet<&str> = addresses.iter().map(String::as_str).collect();
+        if !addresses.is_empty() && old != new {
+            modified_groups.push(DBInstanceIPArray {
+                security_ip_list: addresses.join(","),
+                ..group.clone()
+            });
+        }
+    }
+
+    let require_update = !modified_groups.is_empty();
+    (modified_groups, require_update)
+}
+
+/// ### SDK Implementation ModifySecurityIps
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    instance: &DbInstance,
+    secret: &Secret,
+    groups: &[DBInstanceIPArray],
+) -> Result<()> {
+    for group in groups {
+        let mut params = vec![
+            ("DBInstanceId", instance.id.as_str()),
+            ("SecurityIps", group.security_ip_list.as_str()),
+            (
+                "DBInstanceIPArrayName",
+                group.db_instance_ip_array_name.as_str(),
+            ),
+            ("ModifyMode", "Cover"),
+        ];
+        if !group.security_ip_type.is_empty() {
+            params.push(("SecurityIPType", group.security_ip_type.as_str()));
+        }
+        let basic_request = BasicRequest {
+            machine_type: MachineType::Rds,
+            region_id: &instance.region,
+            action: "ModifySecurityIps",
+            secret,
+            params: &params,
+            body: "",
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        parse_response::<CommonResponse<Empty>>(&result)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,203 @@
+//! # Aliyun Redis/Tair
+//!
+//! Begin with the [`go`] function
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, Empty, MachineType, Secret, parse_response,
+    request_builder,
+};
+use crate::util::family_addresses;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedisInstance {
+    pub id: String,
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeSecurityIpsResponse {
+    #[serde(rename = "SecurityIpGroups")]
+    pub security_ip_groups: DescribeSecurityIpsResponseGroups,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeSecurityIpsResponseGroups {
+    #[serde(rename = "SecurityIpGroup", default)]
+    pub security_ip_group: Vec<SecurityIpGroup>,
+}
+
+/// A whitelist group of a Redis/Tair instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityIpGroup {
+    #[serde(rename = "SecurityIpGroupName")]
+    pub security_ip_group_name: String,
+    #[serde(rename = "SecurityIpGroupAttribute", default)]
+    pub security_ip_group_attribute: String,
+    #[serde(rename = "SecurityIpList", default)]
+    pub security_ip_list: String,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the whitelist groups of a Redis/Tair instance.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::aliyun::{
+///     Secret,
+///     redis::{RedisInstance, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         secret_id: "secret_id".to_string(),
+///         secret_key: "secret_key".to_string(),
+///     };
+///     let instance = RedisInstance {
+///         id: "redis_instance_id".to_string(),
+///         region: "redis_instance_region".to_string(),
+///     };
+///     let _result = go(
+///         &client,
+///         &instance,
+///         &secret,
+///         "current_ipv4",
+///         &["203.0.113.0/24".to_string()],
+///         &["whitelist_group_name".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    instance: impl Borrow<RedisInstance>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    extra_addresses: &[String],
+    matched_groups: &[String],
+) -> Result<()> {
+    let response =
+        list_rules(client, instance.borrow(), secret.borrow()).await?;
+    let (groups, require_update) = compare_rules(
+        &response.response,
+        current_ipv4,
+        extra_addresses,
+        matched_groups,
+    );
+    if require_update {
+        modify_rules(client, instance.borrow(), secret.borrow(), &groups)
+            .await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation DescribeSecurityIps
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    instance: &RedisInstance,
+    secret: &Secret,
+) -> Result<CommonResponse<DescribeSecurityIpsResponse>> {
+    let params = vec![("InstanceId", instance.id.as_str())];
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Redis,
+        region_id: &instance.region,
+        action: "DescribeSecurityIps",
+        secret,
+        params: &params,
+        body: "",
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeSecurityIpsResponse>>(&result)
+}
+
+/// ### SDK Process CompareWhitelistGroups
+///
+/// The groups whose name is in `matched_groups` are replaced with the
+/// current address and the IPv4 `extra_addresses`.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    response: &DescribeSecurityIpsResponse,
+    current_ipv4: &str,
+    extra_addresses: &[String],
+    matched_groups: &[String],
+) -> (Vec<SecurityIpGroup>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_groups.iter().map(|s| s.as_str()).collect();
+
+    let mut modified_groups = Vec::new();
+    if current_ipv4.is_empty() {
+        return (modified_groups, false);
+    }
+    let addresses =
+        family_addresses(current_ipv4, extra_addresses, false, false);
+    let new: HashSet<&str> = addresses.iter().map(String::as_str).collect();
+    for group in &response.security_ip_groups.security_ip_group {
+        let old: HashSet<&str> =
+            group.security_ip_list.split(',').map(str::trim).collect();
+        if matched_set.contains(group.security_ip_group_name.as_str())
+            && old != new
+        {
+            modified_groups.push(SecurityIpGroup {
+                security_ip_list: addresses.join(","),
+                ..group.clone()
+            });
+        }
+    }
+
+    let require_update = !modified_groups.is_empty();
+    (modified_groups, require_update)
+}
+
+/// ### SDK Implementation ModifySecurityIps
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    instance: &RedisInstance,
+    secret: &Secret,
+    groups: &[SecurityIpGroup],
+) -> Result<()> {
+    for group in groups {
+        let params = vec![
+            ("InstanceId", instance.id.as_str()),
+            ("SecurityIps", group.security_ip_list.as_str()),
+            ("SecurityIpGroupName", group.security_ip_group_name.as_str()),
+            ("ModifyMode", "Cover"),
+        ];
+        let basic_request = BasicRequest {
+            machine_type: MachineType::Redis,
+            region_id: &instance.region,
+            action: "ModifySecurityIps",
+            secret,
+            params: &params,
+            body: "",
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        parse_response::<CommonResponse<Empty>>(&result)?;
+    }
+    Ok(())
+}
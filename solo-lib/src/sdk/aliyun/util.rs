@@ -32,6 +32,18 @@ pub enum MachineType {
 
     /// ECS instance
     Ecs,
+
+    /// RDS instance
+    Rds,
+
+    /// Redis/Tair instance
+    Redis,
+
+    /// Cloud Firewall
+    Cloudfw,
+
+    /// Alidns domain
+    Dns,
 }
 
 impl MachineType {
@@ -44,6 +56,23 @@ impl MachineType {
             MachineType::Ecs => {
                 (format!("ecs.{region_id}.aliyuncs.com"), "2014-05-26")
             }
+            MachineType::Rds => ("rds.aliyuncs.com".to_string(), "2014-08-15"),
+            MachineType::Redis => {
+                ("r-kvstore.aliyuncs.com".to_string(), "2015-01-01")
+            }
+            // The Chinese mainland site has a single endpoint
+            MachineType::Cloudfw => match region_id {
+                "" | "cn-hangzhou" => {
+                    ("cloudfw.aliyuncs.com".to_string(), "2017-12-07")
+                }
+                _ => {
+                    (format!("cloudfw.{region_id}.aliyuncs.com"), "2017-12-07")
+                }
+            },
+            MachineType::Dns => match region_id {
+                "" => ("alidns.aliyuncs.com".to_string(), "2015-01-09"),
+                _ => (format!("alidns.{region_id}.aliyuncs.com"), "2015-01-09"),
+            },
         }
     }
 }
@@ -0,0 +1,236 @@
+//! # Cloudflare IP Access Rules
+//!
+//! Begin with the [`go`] function
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use http::Method;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, Secret, parse_response, request_builder,
+};
+
+/// Zone whose IP Access Rules are managed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Zone {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccessRule {
+    #[serde(default, skip_serializing)]
+    pub id: String,
+    pub mode: String,
+    #[serde(default)]
+    pub notes: String,
+    pub configuration: AccessRuleConfiguration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccessRuleConfiguration {
+    /// `ip`, `ip6`, `ip_range`, `asn` or `country`
+    pub target: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccessRuleId {
+    pub id: String,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the IP Access Rules.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::cloudflare::{
+///     Secret,
+///     access_rule::{Zone, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         token: "api_token".to_string(),
+///         endpoint: None,
+///     };
+///     let zone = Zone {
+///         id: "zone_id".to_string(),
+///     };
+///     let _result = go(
+///         &client,
+///         &zone,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["rule_notes".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    zone: impl Borrow<Zone>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_notes: &[String],
+) -> Result<()> {
+    let rules = list_rules(client, zone.borrow(), secret.borrow()).await?;
+    let (rules, require_update) =
+        compare_rules(&rules, current_ipv4, current_ipv6, matched_notes);
+    if require_update {
+        modify_rules(client, zone.borrow(), secret.borrow(), &rules).await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation List IP Access rules
+///
+/// Fetches every page of the rules.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    zone: &Zone,
+    secret: &Secret,
+) -> Result<Vec<AccessRule>> {
+    let mut rules = Vec::new();
+    let mut page = 1;
+    loop {
+        let page_str = page.to_string();
+        let query = [("page", page_str.as_str()), ("per_page", "100")];
+        let basic_request = BasicRequest {
+            method: Method::GET,
+            path: format!("/zones/{}/firewall/access_rules/rules", zone.id),
+            query: &query,
+            payload: String::new(),
+            secret,
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        let response =
+            parse_response::<CommonResponse<Vec<AccessRule>>>(&result)?;
+        rules.extend(response.result);
+        let total_pages = response
+            .result_info
+            .map(|info| info.total_pages)
+            .unwrap_or_default();
+        if page >= total_pages {
+            break;
+        }
+        page += 1;
+    }
+    Ok(rules)
+}
+
+/// ### SDK Process CompareAccessRules
+///
+/// Rules whose notes are in `matched_notes` and that target an IPv4 or
+/// IPv6 address or range are set to the current address of the same
+/// family. A current address that is a CIDR block is set as a range, which
+/// Cloudflare accepts as an IPv4 `/16` or `/24` and an IPv6 `/32`, `/48` or
+/// `/64`.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    rules: &[AccessRule],
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_notes: &[String],
+) -> (Vec<AccessRule>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_notes.iter().map(|s| s.as_str()).collect();
+
+    let mut rules_to_be_modified = Vec::new();
+    for rule in rules {
+        if !matched_set.contains(rule.notes.as_str()) {
+            continue;
+        }
+        let is_ipv6 = match rule.configuration.target.as_str() {
+            "ip" => false,
+            "ip6" => true,
+            "ip_range" => rule.configuration.value.contains(':'),
+            _ => continue,
+        };
+        let current = if is_ipv6 { current_ipv6 } else { current_ipv4 };
+        let target = if current.contains('/') {
+            "ip_range"
+        } else if is_ipv6 {
+            "ip6"
+        } else {
+            "ip"
+        };
+        if !current.is_empty() && rule.configuration.value != current {
+            let mut rule = rule.clone();
+            rule.configuration.target = target.to_string();
+            rule.configuration.value = current.to_string();
+            rules_to_be_modified.push(rule);
+        }
+    }
+
+    let require_update = !rules_to_be_modified.is_empty();
+    (rules_to_be_modified, require_update)
+}
+
+/// ### SDK Implementation Create and delete IP Access rules
+///
+/// The target of an access rule cannot be changed, so a rule with the new
+/// address is created before the old rule is deleted, the address stays
+/// allowed if the creation fails. Rules collapsing to the same address are
+/// created once.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    zone: &Zone,
+    secret: &Secret,
+    rules: &[AccessRule],
+) -> Result<()> {
+    let mut created = HashSet::new();
+    for rule in rules {
+        if created.insert(rule.configuration.value.as_str()) {
+            let basic_request = BasicRequest {
+                method: Method::POST,
+                path: format!("/zones/{}/firewall/access_rules/rules", zone.id),
+                query: &[],
+                payload: serde_json::to_string(rule)?,
+                secret,
+            };
+            let request = request_builder(client, basic_request)?;
+            let result = client.execute(request).await?;
+            let result = result.text().await?;
+            parse_response::<CommonResponse<AccessRuleId>>(&result)?;
+        }
+
+        let basic_request = BasicRequest {
+            method: Method::DELETE,
+            path: format!(
+                "/zones/{}/firewall/access_rules/rules/{}",
+                zone.id, rule.id
+            ),
+            query: &[],
+            payload: String::new(),
+            secret,
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+        parse_response::<CommonResponse<AccessRuleId>>(&result)?;
+    }
+    Ok(())
+}
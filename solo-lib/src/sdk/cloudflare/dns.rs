@@ -0,0 +1,231 @@
+//! # Cloudflare DNS
+//!
+//! Begin with the [`go`] function
+//!
+//! `A` records are set to the current IPv4 address and `AAAA` records to
+//! the current IPv6 address.
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use http::Method;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    access_rule::Zone,
+    util::{
+        BasicRequest, CommonResponse, Secret, parse_response, request_builder,
+    },
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DnsRecord {
+    #[serde(default, skip_serializing)]
+    pub id: String,
+    /// Full name of the record, for example `home.example.com`
+    #[serde(default, skip_serializing)]
+    pub name: String,
+    #[serde(rename = "type", default, skip_serializing)]
+    pub record_type: String,
+    pub content: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+}
+
+/// Options applied to every updated record
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordOptions {
+    /// Whether the record is proxied by Cloudflare, left as is if not set
+    pub proxied: Option<bool>,
+    /// TTL in seconds, `1` means automatic, left as is if not set
+    pub ttl: Option<u32>,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the DNS records.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::cloudflare::{
+///     Secret,
+///     access_rule::Zone,
+///     dns::{RecordOptions, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         token: "api_token".to_string(),
+///         endpoint: None,
+///     };
+///     let zone = Zone {
+///         id: "zone_id".to_string(),
+///     };
+///     let options = RecordOptions {
+///         proxied: Some(false),
+///         ttl: Some(60),
+///     };
+///     let _result = go(
+///         &client,
+///         &zone,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["home.example.com".to_string()],
+///         &options,
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    zone: impl Borrow<Zone>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_names: &[String],
+    options: &RecordOptions,
+) -> Result<()> {
+    let records = list_rules(client, zone.borrow(), secret.borrow()).await?;
+    let (records, require_update) = compare_rules(
+        &records,
+        current_ipv4,
+        current_ipv6,
+        matched_names,
+        options,
+    );
+    if require_update {
+        modify_rules(client, zone.borrow(), secret.borrow(), &records).await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation List DNS Records
+///
+/// Fetches every `A` and `AAAA` record of the zone.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    zone: &Zone,
+    secret: &Secret,
+) -> Result<Vec<DnsRecord>> {
+    let mut records = Vec::new();
+    for record_type in ["A", "AAAA"] {
+        let mut page = 1;
+        loop {
+            let page_str = page.to_string();
+            let query = [
+                ("type", record_type),
+                ("page", page_str.as_str()),
+                ("per_page", "100"),
+            ];
+            let basic_request = BasicRequest {
+                method: Method::GET,
+                path: format!("/zones/{}/dns_records", zone.id),
+                query: &query,
+                payload: String::new(),
+                secret,
+            };
+            let request = request_builder(client, basic_request)?;
+            let result = client.execute(request).await?;
+            let result = result.text().await?;
+
+            let response =
+                parse_response::<CommonResponse<Vec<DnsRecord>>>(&result)?;
+            records.extend(response.result);
+            let total_pages = response
+                .result_info
+                .map(|info| info.total_pages)
+                .unwrap_or_default();
+            if page >= total_pages {
+                break;
+            }
+            page += 1;
+        }
+    }
+    Ok(records)
+}
+
+/// ### SDK Process CompareDnsRecords
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    records: &[DnsRecord],
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_names: &[String],
+    options: &RecordOptions,
+) -> (Vec<DnsRecord>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_names.iter().map(|s| s.as_str()).collect();
+
+    let mut records_to_be_modified = Vec::new();
+    for record in records {
+        if !matched_set.contains(record.name.as_str()) {
+            continue;
+        }
+        let current = match record.record_type.as_str() {
+            "A" => current_ipv4,
+            "AAAA" => current_ipv6,
+            _ => continue,
+        };
+        if current.is_empty() {
+            continue;
+        }
+        let proxied = options.proxied.or(record.proxied);
+        let ttl = options.ttl.or(record.ttl);
+        if record.content != current
+            || record.proxied != proxied
+            || record.ttl != ttl
+        {
+            records_to_be_modified.push(DnsRecord {
+                content: current.to_string(),
+                proxied,
+                ttl,
+                ..record.clone()
+            });
+        }
+    }
+
+    let require_update = !records_to_be_modified.is_empty();
+    (records_to_be_modified, require_update)
+}
+
+/// ### SDK Implementation Update DNS Record
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    zone: &Zone,
+    secret: &Secret,
+    records: &[DnsRecord],
+) -> Result<()> {
+    for record in records {
+        let basic_request = BasicRequest {
+            method: Method::PATCH,
+            path: format!("/zones/{}/dns_records/{}", zone.id, record.id),
+            query: &[],
+            payload: serde_json::to_string(record)?,
+            secret,
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        parse_response::<CommonResponse<DnsRecord>>(&result)?;
+    }
+    Ok(())
+}
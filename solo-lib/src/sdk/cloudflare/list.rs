@@ -0,0 +1,208 @@
+//! # Cloudflare IP Lists
+//!
+//! Begin with the [`go`] function
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use http::Method;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, Secret, parse_response, request_builder,
+};
+
+/// Account-level IP List
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpList {
+    pub account_id: String,
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListItem {
+    #[serde(default, skip_serializing)]
+    pub id: String,
+    pub ip: String,
+    #[serde(default)]
+    pub comment: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ModifyListItemsResponse {
+    pub operation_id: String,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the IP List items.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::cloudflare::{
+///     Secret,
+///     list::{IpList, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         token: "api_token".to_string(),
+///         endpoint: None,
+///     };
+///     let list = IpList {
+///         account_id: "account_id".to_string(),
+///         id: "list_id".to_string(),
+///     };
+///     let _result = go(
+///         &client,
+///         &list,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["item_comment".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    list: impl Borrow<IpList>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_comments: &[String],
+) -> Result<()> {
+    let items = list_rules(client, list.borrow(), secret.borrow()).await?;
+    let (items, require_update) =
+        compare_rules(&items, current_ipv4, current_ipv6, matched_comments);
+    if require_update {
+        modify_rules(client, list.borrow(), secret.borrow(), &items).await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation Get list items
+///
+/// Fetches every page of the list.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    list: &IpList,
+    secret: &Secret,
+) -> Result<Vec<ListItem>> {
+    let mut items = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let mut query = vec![("per_page", "500")];
+        if !cursor.is_empty() {
+            query.push(("cursor", cursor.as_str()));
+        }
+        let basic_request = BasicRequest {
+            method: Method::GET,
+            path: format!(
+                "/accounts/{}/rules/lists/{}/items",
+                list.account_id, list.id
+            ),
+            query: &query,
+            payload: String::new(),
+            secret,
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        let response =
+            parse_response::<CommonResponse<Vec<ListItem>>>(&result)?;
+        items.extend(response.result);
+        match response
+            .result_info
+            .and_then(|info| info.cursors)
+            .and_then(|cursors| cursors.after)
+        {
+            Some(after) if !after.is_empty() => cursor = after,
+            _ => break,
+        }
+    }
+    Ok(items)
+}
+
+/// ### SDK Process CompareListItems
+///
+/// Items whose comment is in `matched_comments` are set to the current
+/// address of the same family. All items are returned, since the list is
+/// replaced as a whole.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    items: &[ListItem],
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_comments: &[String],
+) -> (Vec<ListItem>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_comments.iter().map(|s| s.as_str()).collect();
+
+    let mut require_update = false;
+    let items = items
+        .iter()
+        .map(|item| {
+            let current = if item.ip.contains(':') {
+                current_ipv6
+            } else {
+                current_ipv4
+            };
+            if matched_set.contains(item.comment.as_str())
+                && !current.is_empty()
+                && item.ip != current
+            {
+                require_update = true;
+                ListItem {
+                    ip: current.to_string(),
+                    ..item.clone()
+                }
+            } else {
+                item.clone()
+            }
+        })
+        .collect();
+
+    (items, require_update)
+}
+
+/// ### SDK Implementation Update all list items
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    list: &IpList,
+    secret: &Secret,
+    items: &[ListItem],
+) -> Result<CommonResponse<ModifyListItemsResponse>> {
+    let payload = serde_json::to_string(items)?;
+    let basic_request = BasicRequest {
+        method: Method::PUT,
+        path: format!(
+            "/accounts/{}/rules/lists/{}/items",
+            list.account_id, list.id
+        ),
+        query: &[],
+        payload,
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<ModifyListItemsResponse>>(&result)
+}
@@ -0,0 +1,13 @@
+//! # Cloudflare SDK
+//!
+//! Supports:
+//! - IP Lists [`list`]
+//! - IP Access Rules [`access_rule`]
+//! - DNS [`dns`]
+
+pub mod access_rule;
+pub mod dns;
+pub mod list;
+
+mod util;
+pub use util::*;
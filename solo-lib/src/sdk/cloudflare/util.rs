@@ -0,0 +1,107 @@
+use anyhow::Result;
+use http::Method;
+use reqwest::{Client, Request};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_str};
+
+use crate::SdkError;
+
+/// The default base URL of the Cloudflare API
+pub const DEFAULT_ENDPOINT: &str = "https://api.cloudflare.com/client/v4";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secret {
+    /// API token
+    pub token: String,
+    /// Base URL of the API, defaults to [`DEFAULT_ENDPOINT`]
+    pub endpoint: Option<String>,
+}
+
+/// Common response
+///
+/// The response from the API is wrapped in this struct
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CommonResponse<T> {
+    pub result: T,
+    #[serde(default)]
+    pub result_info: Option<ResultInfo>,
+}
+
+/// Pagination info
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct ResultInfo {
+    #[serde(default)]
+    pub page: u32,
+    #[serde(default)]
+    pub total_pages: u32,
+    #[serde(default)]
+    pub cursors: Option<Cursors>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Cursors {
+    #[serde(default)]
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct BasicRequest<'a> {
+    pub(super) method: Method,
+    pub(super) path: String,
+    pub(super) query: &'a [(&'a str, &'a str)],
+    pub(super) payload: String,
+    pub(super) secret: &'a Secret,
+}
+
+pub(super) fn request_builder(
+    client: &Client,
+    basic_request: BasicRequest,
+) -> Result<Request> {
+    let endpoint = basic_request
+        .secret
+        .endpoint
+        .as_deref()
+        .unwrap_or(DEFAULT_ENDPOINT)
+        .trim_end_matches('/');
+    let url = format!("{endpoint}{}", basic_request.path);
+
+    Ok(client
+        .request(basic_request.method, url)
+        .bearer_auth(&basic_request.secret.token)
+        .header("Content-Type", "application/json")
+        .query(basic_request.query)
+        .body(basic_request.payload)
+        .build()?)
+}
+
+fn to_error_response(response: &str) -> Option<SdkError> {
+    let response: Value = from_str(response).ok()?;
+    if response.get("success")?.as_bool()? {
+        return None;
+    }
+    let error = response.get("errors")?.as_array()?.first();
+    let code = error
+        .and_then(|e| e.get("code"))
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let message = error
+        .and_then(|e| e.get("message"))
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    Some(SdkError {
+        code,
+        message,
+        request_id: String::new(),
+    })
+}
+
+pub(super) fn parse_response<'a, T: Deserialize<'a>>(
+    result: &'a str,
+) -> Result<T> {
+    if let Some(error) = to_error_response(result) {
+        Err(error.into())
+    } else {
+        Ok(from_str::<T>(result)?)
+    }
+}
@@ -0,0 +1,202 @@
+//! # DigitalOcean Cloud Firewalls
+//!
+//! Begin with the [`go`] function
+//!
+//! Firewall rules have no description, so inbound rules are matched by
+//! `{protocol}:{ports}`, for example `tcp:22`.
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use http::Method;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::util::{BasicRequest, Secret, parse_response, request_builder};
+use crate::util::replace_addresses;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FirewallResponse {
+    pub firewall: Firewall,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Firewall {
+    #[serde(default, skip_serializing)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub inbound_rules: Vec<InboundRule>,
+    #[serde(default)]
+    pub outbound_rules: Vec<Value>,
+    #[serde(default)]
+    pub droplet_ids: Vec<u64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InboundRule {
+    pub protocol: String,
+    #[serde(default)]
+    pub ports: String,
+    pub sources: Sources,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Sources {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<String>,
+    /// Droplets, tags, load balancers and Kubernetes clusters
+    #[serde(flatten)]
+    pub others: Map<String, Value>,
+}
+
+impl InboundRule {
+    /// The key used to match the rule, `{protocol}:{ports}`
+    pub fn key(&self) -> String {
+        format!("{}:{}", self.protocol, self.ports)
+    }
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the firewall rules.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::digitalocean::{Secret, firewall::go};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         token: "token".to_string(),
+///         endpoint: None,
+///     };
+///     let _result = go(
+///         &client,
+///         "firewall_id",
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["203.0.113.0/24".to_string()],
+///         &["tcp:22".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    firewall_id: &str,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_rules: &[String],
+) -> Result<()> {
+    let response = list_rules(client, firewall_id, secret.borrow()).await?;
+    let (firewall, require_update) = compare_rules(
+        &response.firewall,
+        current_ipv4,
+        current_ipv6,
+        extra_addresses,
+        matched_rules,
+    );
+    if require_update {
+        modify_rules(client, firewall_id, secret.borrow(), &firewall).await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation Retrieve an Existing Firewall
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    firewall_id: &str,
+    secret: &Secret,
+) -> Result<FirewallResponse> {
+    let basic_request = BasicRequest {
+        method: Method::GET,
+        path: format!("/firewalls/{firewall_id}"),
+        payload: String::new(),
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<FirewallResponse>(&result)
+}
+
+/// ### SDK Process CompareFirewallRules
+///
+/// The source addresses of the inbound rules in `matched_rules` are
+/// replaced with the current addresses and `extra_addresses`. The whole
+/// firewall is returned, since it is updated as a whole.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    firewall: &Firewall,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_rules: &[String],
+) -> (Firewall, bool) {
+    let matched_set: HashSet<&str> =
+        matched_rules.iter().map(|s| s.as_str()).collect();
+
+    let mut require_update = false;
+    let mut firewall = firewall.clone();
+    for rule in &mut firewall.inbound_rules {
+        if !matched_set.contains(rule.key().as_str()) {
+            continue;
+        }
+        let addresses = replace_addresses(
+            &rule.sources.addresses,
+            current_ipv4,
+            current_ipv6,
+            extra_addresses,
+            false,
+        );
+        let old: HashSet<&String> = rule.sources.addresses.iter().collect();
+        let new: HashSet<&String> = addresses.iter().collect();
+        if old != new {
+            rule.sources.addresses = addresses;
+            require_update = true;
+        }
+    }
+
+    (firewall, require_update)
+}
+
+/// ### SDK Implementation Update a Firewall
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    firewall_id: &str,
+    secret: &Secret,
+    firewall: &Firewall,
+) -> Result<FirewallResponse> {
+    let basic_request = BasicRequest {
+        method: Method::PUT,
+        path: format!("/firewalls/{firewall_id}"),
+        payload: serde_json::to_string(firewall)?,
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<FirewallResponse>(&result)
+}
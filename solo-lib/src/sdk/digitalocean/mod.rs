@@ -0,0 +1,9 @@
+//! # DigitalOcean SDK
+//!
+//! Supports:
+//! - Cloud Firewalls [`firewall`]
+
+pub mod firewall;
+
+mod util;
+pub use util::*;
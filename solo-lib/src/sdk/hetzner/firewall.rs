@@ -0,0 +1,192 @@
+//! # Hetzner Cloud Firewalls
+//!
+//! Begin with the [`go`] function
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use http::Method;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::util::{BasicRequest, Secret, parse_response, request_builder};
+use crate::util::replace_addresses;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FirewallResponse {
+    pub firewall: Firewall,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Firewall {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Rule {
+    /// `in` or `out`
+    pub direction: String,
+    pub protocol: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub source_ips: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub destination_ips: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct SetRulesRequest<'a> {
+    rules: &'a [Rule],
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the firewall rules.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::hetzner::{Secret, firewall::go};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         token: "token".to_string(),
+///         endpoint: None,
+///     };
+///     let _result = go(
+///         &client,
+///         "firewall_id",
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["203.0.113.0/24".to_string()],
+///         &["rule_description".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    firewall_id: &str,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_descriptions: &[String],
+) -> Result<()> {
+    let response = list_rules(client, firewall_id, secret.borrow()).await?;
+    let (rules, require_update) = compare_rules(
+        &response.firewall.rules,
+        current_ipv4,
+        current_ipv6,
+        extra_addresses,
+        matched_descriptions,
+    );
+    if require_update {
+        modify_rules(client, firewall_id, secret.borrow(), &rules).await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation Get a Firewall
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    firewall_id: &str,
+    secret: &Secret,
+) -> Result<FirewallResponse> {
+    let basic_request = BasicRequest {
+        method: Method::GET,
+        path: format!("/firewalls/{firewall_id}"),
+        payload: String::new(),
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<FirewallResponse>(&result)
+}
+
+/// ### SDK Process CompareFirewallRules
+///
+/// The source IPs of the inbound rules whose description is in
+/// `matched_descriptions` are replaced with the current addresses and
+/// `extra_addresses`. All rules are returned, since they are set as a whole.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    rules: &[Rule],
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_descriptions: &[String],
+) -> (Vec<Rule>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_descriptions.iter().map(|s| s.as_str()).collect();
+
+    let mut require_update = false;
+    let mut rules = rules.to_vec();
+    for rule in &mut rules {
+        let is_matched = rule
+            .description
+            .as_deref()
+            .is_some_and(|description| matched_set.contains(description));
+        if rule.direction != "in" || !is_matched {
+            continue;
+        }
+        let source_ips = replace_addresses(
+            &rule.source_ips,
+            current_ipv4,
+            current_ipv6,
+            extra_addresses,
+            true,
+        );
+        let old: HashSet<&String> = rule.source_ips.iter().collect();
+        let new: HashSet<&String> = source_ips.iter().collect();
+        if old != new {
+            rule.source_ips = source_ips;
+            require_update = true;
+        }
+    }
+
+    (rules, require_update)
+}
+
+/// ### SDK Implementation Set Rules
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    firewall_id: &str,
+    secret: &Secret,
+    rules: &[Rule],
+) -> Result<Value> {
+    let basic_request = BasicRequest {
+        method: Method::POST,
+        path: format!("/firewalls/{firewall_id}/actions/set_rules"),
+        payload: serde_json::to_string(&SetRulesRequest { rules })?,
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<Value>(&result)
+}
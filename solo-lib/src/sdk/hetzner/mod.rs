@@ -0,0 +1,9 @@
+//! # Hetzner Cloud SDK
+//!
+//! Supports:
+//! - Firewalls [`firewall`]
+
+pub mod firewall;
+
+mod util;
+pub use util::*;
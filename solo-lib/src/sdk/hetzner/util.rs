@@ -0,0 +1,68 @@
+use anyhow::Result;
+use http::Method;
+use reqwest::{Client, Request};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_str};
+
+use crate::SdkError;
+
+/// The default base URL of the Hetzner Cloud API
+pub const DEFAULT_ENDPOINT: &str = "https://api.hetzner.cloud/v1";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secret {
+    /// API token
+    pub token: String,
+    /// Base URL of the API, defaults to [`DEFAULT_ENDPOINT`]
+    pub endpoint: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct BasicRequest<'a> {
+    pub(super) method: Method,
+    pub(super) path: String,
+    pub(super) payload: String,
+    pub(super) secret: &'a Secret,
+}
+
+pub(super) fn request_builder(
+    client: &Client,
+    basic_request: BasicRequest,
+) -> Result<Request> {
+    let endpoint = basic_request
+        .secret
+        .endpoint
+        .as_deref()
+        .unwrap_or(DEFAULT_ENDPOINT)
+        .trim_end_matches('/');
+    let url = format!("{endpoint}{}", basic_request.path);
+
+    Ok(client
+        .request(basic_request.method, url)
+        .bearer_auth(&basic_request.secret.token)
+        .header("Content-Type", "application/json")
+        .body(basic_request.payload)
+        .build()?)
+}
+
+fn to_error_response(response: &str) -> Option<SdkError> {
+    let response: Value = from_str(response).ok()?;
+    let error = response.get("error")?;
+    let code = error.get("code")?.as_str()?.to_string();
+    let message = error.get("message")?.as_str()?.to_string();
+    Some(SdkError {
+        code,
+        message,
+        request_id: String::new(),
+    })
+}
+
+pub(super) fn parse_response<'a, T: Deserialize<'a>>(
+    result: &'a str,
+) -> Result<T> {
+    if let Some(error) = to_error_response(result) {
+        Err(error.into())
+    } else {
+        Ok(from_str::<T>(result)?)
+    }
+}
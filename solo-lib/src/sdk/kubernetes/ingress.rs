@@ -0,0 +1,206 @@
+//! # Kubernetes Ingress allowlist annotation
+//!
+//! Begin with the [`go`] function
+//!
+//! The annotations hold comma separated CIDRs, such as the
+//! [`WHITELIST_SOURCE_RANGE`] annotation of ingress-nginx.
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use http::Method;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use super::util::{
+    BasicRequest, MERGE_PATCH, ObjectMeta, Resource, Secret, parse_response,
+    request_builder,
+};
+use crate::util::replace_addresses;
+
+/// The allowlist annotation of ingress-nginx
+pub const WHITELIST_SOURCE_RANGE: &str =
+    "nginx.ingress.kubernetes.io/whitelist-source-range";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Ingress {
+    pub metadata: ObjectMeta,
+    /// Spec and status, left untouched
+    #[serde(flatten)]
+    pub others: Map<String, Value>,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the ingress annotations.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::kubernetes::{
+///     Resource, Secret,
+///     ingress::{WHITELIST_SOURCE_RANGE, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         token: "token".to_string(),
+///         endpoint: "https://127.0.0.1:6443".to_string(),
+///         ..Default::default()
+///     };
+///     let _result = go(
+///         &client,
+///         &Resource::from_id("default/admin"),
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["203.0.113.0/24".to_string()],
+///         &[WHITELIST_SOURCE_RANGE.to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    resource: impl Borrow<Resource>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_annotations: &[String],
+) -> Result<()> {
+    let ingress =
+        list_rules(client, resource.borrow(), secret.borrow()).await?;
+    let (ingress, require_update) = compare_rules(
+        &ingress,
+        current_ipv4,
+        current_ipv6,
+        extra_addresses,
+        matched_annotations,
+    );
+    if require_update {
+        modify_rules(client, resource.borrow(), secret.borrow(), &ingress)
+            .await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation Read Ingress
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    resource: &Resource,
+    secret: &Secret,
+) -> Result<Ingress> {
+    let basic_request = BasicRequest {
+        method: Method::GET,
+        path: format!(
+            "/apis/networking.k8s.io/v1/namespaces/{}/ingresses/{}",
+            resource.namespace, resource.name
+        ),
+        content_type: "application/json",
+        payload: String::new(),
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<Ingress>(&result)
+}
+
+/// ### SDK Process CompareAnnotations
+///
+/// The CIDRs of every annotation in `matched_annotations` are replaced with
+/// the current addresses and `extra_addresses`, a missing annotation is
+/// created.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    ingress: &Ingress,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_annotations: &[String],
+) -> (Ingress, bool) {
+    let mut require_update = false;
+    let mut ingress = ingress.clone();
+    for annotation in matched_annotations {
+        let cidrs: Vec<String> = ingress
+            .metadata
+            .annotations
+            .get(annotation)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|cidr| !cidr.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let replaced = replace_addresses(
+            &cidrs,
+            current_ipv4,
+            current_ipv6,
+            extra_addresses,
+            true,
+        );
+        let old: HashSet<&String> = cidrs.iter().collect();
+        let new: HashSet<&String> = replaced.iter().collect();
+        if old != new {
+            ingress
+                .metadata
+                .annotations
+                .insert(annotation.clone(), replaced.join(","));
+            require_update = true;
+        }
+    }
+
+    (ingress, require_update)
+}
+
+/// ### SDK Implementation Patch Ingress
+///
+/// The annotations are sent as a merge patch, guarded by the resource
+/// version that was read.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    resource: &Resource,
+    secret: &Secret,
+    ingress: &Ingress,
+) -> Result<Ingress> {
+    let patch = json!({
+        "metadata": {
+            "resourceVersion": ingress.metadata.resource_version,
+            "annotations": ingress.metadata.annotations,
+        },
+    });
+    let basic_request = BasicRequest {
+        method: Method::PATCH,
+        path: format!(
+            "/apis/networking.k8s.io/v1/namespaces/{}/ingresses/{}",
+            resource.namespace, resource.name
+        ),
+        content_type: MERGE_PATCH,
+        payload: serde_json::to_string(&patch)?,
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<Ingress>(&result)
+}
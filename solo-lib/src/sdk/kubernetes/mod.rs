@@ -0,0 +1,17 @@
+//! # Kubernetes SDK
+//!
+//! Supports:
+//! - NetworkPolicy [`network_policy`]
+//! - Ingress allowlist annotation [`ingress`]
+//!
+//! Requests are authenticated with a bearer token or a client certificate,
+//! which can be read from a kubeconfig by [`Secret::from_kubeconfig`] or
+//! from the service account of a pod by [`Secret::in_cluster`]. The client
+//! passed to the functions must trust the certificate authority of the
+//! cluster.
+
+pub mod ingress;
+pub mod network_policy;
+
+mod util;
+pub use util::*;
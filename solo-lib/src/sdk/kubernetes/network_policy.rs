@@ -0,0 +1,291 @@
+//! # Kubernetes NetworkPolicy
+//!
+//! Begin with the [`go`] function
+//!
+//! Ingress rules have no name, so they are matched by the `{protocol}:{port}`
+//! of their ports, for example `TCP:443`. See [`IngressRule::key`].
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use http::Method;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+
+use super::util::{
+    BasicRequest, MERGE_PATCH, ObjectMeta, Resource, Secret, parse_response,
+    request_builder,
+};
+use crate::util::replace_addresses;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkPolicy {
+    pub metadata: ObjectMeta,
+    pub spec: NetworkPolicySpec,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetworkPolicySpec {
+    #[serde(default)]
+    pub ingress: Vec<IngressRule>,
+    /// Pod selector, policy types and egress rules
+    #[serde(flatten)]
+    pub others: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IngressRule {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub from: Vec<Peer>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<Port>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Peer {
+    #[serde(
+        rename = "ipBlock",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ip_block: Option<IpBlock>,
+    /// Pod and namespace selectors
+    #[serde(flatten)]
+    pub others: Map<String, Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IpBlock {
+    pub cidr: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub except: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Port {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    /// Port number or name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<Value>,
+    #[serde(
+        rename = "endPort",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub end_port: Option<u16>,
+}
+
+impl IngressRule {
+    /// The key used to match the rule
+    ///
+    /// `{protocol}:{port}` of each port joined by `,`, such as `TCP:80,TCP:443`
+    /// or `TCP:30000-32767`. A missing port is written as `*`, and a rule
+    /// without ports is `all`.
+    pub fn key(&self) -> String {
+        if self.ports.is_empty() {
+            return "all".to_string();
+        }
+        self.ports
+            .iter()
+            .map(|port| {
+                let protocol = port.protocol.as_deref().unwrap_or("TCP");
+                let number = match &port.port {
+                    Some(Value::String(name)) => name.clone(),
+                    Some(value) => value.to_string(),
+                    None => "*".to_string(),
+                };
+                match port.end_port {
+                    Some(end_port) => format!("{protocol}:{number}-{end_port}"),
+                    None => format!("{protocol}:{number}"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the network policy.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::kubernetes::{Resource, Secret, network_policy::go};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         token: "token".to_string(),
+///         endpoint: "https://127.0.0.1:6443".to_string(),
+///         ..Default::default()
+///     };
+///     let _result = go(
+///         &client,
+///         &Resource::from_id("default/allow-office"),
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["203.0.113.0/24".to_string()],
+///         &["TCP:443".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    resource: impl Borrow<Resource>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_rules: &[String],
+) -> Result<()> {
+    let policy = list_rules(client, resource.borrow(), secret.borrow()).await?;
+    let (policy, require_update) = compare_rules(
+        &policy,
+        current_ipv4,
+        current_ipv6,
+        extra_addresses,
+        matched_rules,
+    );
+    if require_update {
+        modify_rules(client, resource.borrow(), secret.borrow(), &policy)
+            .await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation Read NetworkPolicy
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    resource: &Resource,
+    secret: &Secret,
+) -> Result<NetworkPolicy> {
+    let basic_request = BasicRequest {
+        method: Method::GET,
+        path: format!(
+            "/apis/networking.k8s.io/v1/namespaces/{}/networkpolicies/{}",
+            resource.namespace, resource.name
+        ),
+        content_type: "application/json",
+        payload: String::new(),
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<NetworkPolicy>(&result)
+}
+
+/// ### SDK Process CompareNetworkPolicy
+///
+/// The `ipBlock` peers of the ingress rules in `matched_rules` are replaced
+/// with the current addresses and `extra_addresses`, other peers are kept.
+/// The whole policy is returned, since the ingress rules are patched as a
+/// whole.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    policy: &NetworkPolicy,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_rules: &[String],
+) -> (NetworkPolicy, bool) {
+    let matched_set: HashSet<&str> =
+        matched_rules.iter().map(|s| s.as_str()).collect();
+
+    let mut require_update = false;
+    let mut policy = policy.clone();
+    for rule in &mut policy.spec.ingress {
+        if !matched_set.contains(rule.key().as_str()) {
+            continue;
+        }
+        let cidrs: Vec<String> = rule
+            .from
+            .iter()
+            .filter_map(|peer| peer.ip_block.as_ref())
+            .map(|ip_block| ip_block.cidr.clone())
+            .collect();
+        let replaced = replace_addresses(
+            &cidrs,
+            current_ipv4,
+            current_ipv6,
+            extra_addresses,
+            true,
+        );
+        let old: HashSet<&String> = cidrs.iter().collect();
+        let new: HashSet<&String> = replaced.iter().collect();
+        if old == new {
+            continue;
+        }
+
+        rule.from.retain(|peer| {
+            peer.ip_block
+                .as_ref()
+                .is_none_or(|ip_block| new.contains(&ip_block.cidr))
+        });
+        for cidr in replaced.iter().filter(|cidr| !old.contains(cidr)) {
+            rule.from.push(Peer {
+                ip_block: Some(IpBlock {
+                    cidr: cidr.clone(),
+                    except: Vec::new(),
+                }),
+                others: Map::new(),
+            });
+        }
+        require_update = true;
+    }
+
+    (policy, require_update)
+}
+
+/// ### SDK Implementation Patch NetworkPolicy
+///
+/// The ingress rules are sent as a merge patch, guarded by the resource
+/// version that was read.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    resource: &Resource,
+    secret: &Secret,
+    policy: &NetworkPolicy,
+) -> Result<NetworkPolicy> {
+    let patch = json!({
+        "metadata": {
+            "resourceVersion": policy.metadata.resource_version,
+        },
+        "spec": {
+            "ingress": policy.spec.ingress,
+        },
+    });
+    let basic_request = BasicRequest {
+        method: Method::PATCH,
+        path: format!(
+            "/apis/networking.k8s.io/v1/namespaces/{}/networkpolicies/{}",
+            resource.namespace, resource.name
+        ),
+        content_type: MERGE_PATCH,
+        payload: serde_json::to_string(&patch)?,
+        secret,
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<NetworkPolicy>(&result)
+}
@@ -0,0 +1,299 @@
+use std::{collections::BTreeMap, env, fs};
+
+use anyhow::{Result, anyhow};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use http::Method;
+use reqwest::{Client, Request};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, from_str};
+
+use crate::SdkError;
+
+/// Content type of a JSON merge patch
+pub(super) const MERGE_PATCH: &str = "application/merge-patch+json";
+
+/// Directory the service account of a pod is mounted to
+pub const SERVICE_ACCOUNT_PATH: &str =
+    "/var/run/secrets/kubernetes.io/serviceaccount";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Secret {
+    /// Bearer token, such as a service account token
+    pub token: String,
+    /// URL of the API server, for example `https://127.0.0.1:6443`
+    pub endpoint: String,
+    /// PEM encoded certificate authority of the API server, the system
+    /// roots are trusted if not set
+    #[serde(default)]
+    pub certificate_authority: Option<String>,
+    /// PEM encoded client certificate followed by its private key
+    #[serde(default)]
+    pub client_identity: Option<String>,
+    /// Skip the verification of the API server certificate
+    #[serde(default)]
+    pub insecure_skip_tls_verify: bool,
+}
+
+impl Secret {
+    /// Read the API server URL and the credentials from a kubeconfig.
+    ///
+    /// The `current-context` is used if `context` is not set. Token and
+    /// client certificate authentication are supported, exec plugins and
+    /// auth providers are not.
+    pub fn from_kubeconfig(
+        content: &str,
+        context: Option<&str>,
+    ) -> Result<Self> {
+        let config: Kubeconfig = serde_yaml::from_str(content)?;
+        let context_name = context.unwrap_or(&config.current_context);
+        let context =
+            config
+                .contexts
+                .iter()
+                .find(|c| c.name == context_name)
+                .ok_or_else(|| anyhow!("context {context_name} not found"))?;
+        let cluster = config
+            .clusters
+            .iter()
+            .find(|c| c.name == context.context.cluster)
+            .ok_or_else(|| {
+                anyhow!("cluster {} not found", context.context.cluster)
+            })?;
+        let user = config
+            .users
+            .iter()
+            .find(|u| u.name == context.context.user)
+            .ok_or_else(|| {
+                anyhow!("user {} not found", context.context.user)
+            })?;
+
+        let token = match (&user.user.token, &user.user.token_file) {
+            (Some(token), _) => token.clone(),
+            (None, Some(token_file)) => {
+                fs::read_to_string(token_file)?.trim().to_string()
+            }
+            (None, None) => String::new(),
+        };
+        let certificate = read_pem(
+            user.user.client_certificate_data.as_deref(),
+            user.user.client_certificate.as_deref(),
+        )?;
+        let key = read_pem(
+            user.user.client_key_data.as_deref(),
+            user.user.client_key.as_deref(),
+        )?;
+        let client_identity = match (certificate, key) {
+            (Some(certificate), Some(key)) => {
+                Some(format!("{certificate}\n{key}"))
+            }
+            (None, None) => None,
+            _ => {
+                return Err(anyhow!(
+                    "user {} needs both a client certificate and a client key",
+                    user.name
+                ));
+            }
+        };
+        if token.is_empty() && client_identity.is_none() {
+            return Err(anyhow!(
+                "user {} has neither a token nor a client certificate, exec \
+                 plugins and auth providers are not supported",
+                user.name
+            ));
+        }
+
+        Ok(Self {
+            token,
+            endpoint: cluster.cluster.server.clone(),
+            certificate_authority: read_pem(
+                cluster.cluster.certificate_authority_data.as_deref(),
+                cluster.cluster.certificate_authority.as_deref(),
+            )?,
+            client_identity,
+            insecure_skip_tls_verify: cluster.cluster.insecure_skip_tls_verify,
+        })
+    }
+
+    /// Read the API server URL and the credentials of the service account
+    /// mounted into the pod solo runs in.
+    pub fn in_cluster() -> Result<Self> {
+        let host = env::var("KUBERNETES_SERVICE_HOST")?;
+        let port = env::var("KUBERNETES_SERVICE_PORT")?;
+        let host = if host.contains(':') {
+            format!("[{host}]")
+        } else {
+            host
+        };
+        Ok(Self {
+            token: fs::read_to_string(format!("{SERVICE_ACCOUNT_PATH}/token"))?
+                .trim()
+                .to_string(),
+            endpoint: format!("https://{host}:{port}"),
+            certificate_authority: Some(fs::read_to_string(format!(
+                "{SERVICE_ACCOUNT_PATH}/ca.crt"
+            ))?),
+            client_identity: None,
+            insecure_skip_tls_verify: false,
+        })
+    }
+}
+
+/// Read a PEM from its base64 encoded `-data` field or else from its file.
+fn read_pem(data: Option<&str>, path: Option<&str>) -> Result<Option<String>> {
+    match (data, path) {
+        (Some(data), _) => {
+            Ok(Some(String::from_utf8(STANDARD.decode(data.trim())?)?))
+        }
+        (None, Some(path)) => Ok(Some(fs::read_to_string(path)?)),
+        (None, None) => Ok(None),
+    }
+}
+
+/// A namespaced resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resource {
+    pub namespace: String,
+    pub name: String,
+}
+
+impl Resource {
+    /// Parse `{namespace}/{name}`, the namespace defaults to `default`.
+    pub fn from_id(id: &str) -> Self {
+        let (namespace, name) = id.split_once('/').unwrap_or(("default", id));
+        Self {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectMeta {
+    pub name: String,
+    #[serde(default)]
+    pub namespace: String,
+    #[serde(rename = "resourceVersion", default)]
+    pub resource_version: String,
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Kubeconfig {
+    #[serde(rename = "current-context", default)]
+    current_context: String,
+    #[serde(default)]
+    contexts: Vec<NamedContext>,
+    #[serde(default)]
+    clusters: Vec<NamedCluster>,
+    #[serde(default)]
+    users: Vec<NamedUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedContext {
+    name: String,
+    context: Context,
+}
+
+#[derive(Debug, Deserialize)]
+struct Context {
+    cluster: String,
+    user: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedCluster {
+    name: String,
+    cluster: Cluster,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cluster {
+    server: String,
+    #[serde(rename = "certificate-authority")]
+    certificate_authority: Option<String>,
+    #[serde(rename = "certificate-authority-data")]
+    certificate_authority_data: Option<String>,
+    #[serde(rename = "insecure-skip-tls-verify", default)]
+    insecure_skip_tls_verify: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct NamedUser {
+    name: String,
+    user: User,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    token: Option<String>,
+    #[serde(rename = "tokenFile")]
+    token_file: Option<String>,
+    #[serde(rename = "client-certificate")]
+    client_certificate: Option<String>,
+    #[serde(rename = "client-certificate-data")]
+    client_certificate_data: Option<String>,
+    #[serde(rename = "client-key")]
+    client_key: Option<String>,
+    #[serde(rename = "client-key-data")]
+    client_key_data: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct BasicRequest<'a> {
+    pub(super) method: Method,
+    pub(super) path: String,
+    pub(super) content_type: &'static str,
+    pub(super) payload: String,
+    pub(super) secret: &'a Secret,
+}
+
+pub(super) fn request_builder(
+    client: &Client,
+    basic_request: BasicRequest,
+) -> Result<Request> {
+    let endpoint = basic_request.secret.endpoint.trim_end_matches('/');
+    let url = format!("{endpoint}{}", basic_request.path);
+
+    let mut builder = client
+        .request(basic_request.method, url)
+        .header("Accept", "application/json")
+        .header("Content-Type", basic_request.content_type)
+        .body(basic_request.payload);
+    if !basic_request.secret.token.is_empty() {
+        builder = builder.bearer_auth(&basic_request.secret.token);
+    }
+    Ok(builder.build()?)
+}
+
+/// Failures are returned as a `Status` object
+fn to_error_response(response: &str) -> Option<SdkError> {
+    let response: Value = from_str(response).ok()?;
+    if response.get("kind")?.as_str()? != "Status"
+        || response.get("status")?.as_str()? != "Failure"
+    {
+        return None;
+    }
+    let code = response
+        .get("reason")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let message = response.get("message")?.as_str()?.to_string();
+    Some(SdkError {
+        code,
+        message,
+        request_id: String::new(),
+    })
+}
+
+pub(super) fn parse_response<'a, T: Deserialize<'a>>(
+    result: &'a str,
+) -> Result<T> {
+    if let Some(error) = to_error_response(result) {
+        Err(error.into())
+    } else {
+        Ok(from_str::<T>(result)?)
+    }
+}
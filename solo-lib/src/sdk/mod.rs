@@ -15,5 +15,10 @@
 //! Note that you can also provide both IPv4 and IPv6 addresses to replace both rules.
 
 pub mod aliyun;
+pub mod cloudflare;
+pub mod digitalocean;
+pub mod hetzner;
+pub mod kubernetes;
 pub mod qcloud;
 pub mod rainyun;
+pub mod vultr;
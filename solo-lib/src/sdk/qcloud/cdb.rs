@@ -0,0 +1,78 @@
+//! # Qcloud TencentDB for MySQL
+//!
+//! TencentDB controls access with the same VPC security groups as CVM, so
+//! this module only looks up the security groups bound to a database
+//! instance. Update them with the [`cvm`](super::cvm) module.
+
+use std::borrow::Borrow;
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, MachineType, Secret, parse_response,
+    request_builder,
+};
+
+/// TencentDB instance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DbInstance {
+    pub id: String,
+    pub region: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DescribeDBSecurityGroupsRequest {
+    #[serde(rename = "InstanceId")]
+    instance_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DescribeDBSecurityGroupsResponse {
+    #[serde(rename = "Groups", default)]
+    pub groups: Vec<SecurityGroupInfo>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecurityGroupInfo {
+    #[serde(rename = "SecurityGroupId")]
+    pub security_group_id: String,
+    #[serde(rename = "SecurityGroupName", default)]
+    pub security_group_name: String,
+}
+
+/// Returns whether the given ID is a TencentDB for MySQL instance ID
+/// (`cdb-…`).
+pub fn is_instance_id(id: &str) -> bool {
+    id.starts_with("cdb-")
+}
+
+/// ### SDK Implementation DescribeDBSecurityGroups
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn describe_security_groups(
+    client: &Client,
+    instance: impl Borrow<DbInstance>,
+    secret: impl Borrow<Secret>,
+) -> Result<CommonResponse<DescribeDBSecurityGroupsResponse>> {
+    let instance = instance.borrow();
+
+    let request = DescribeDBSecurityGroupsRequest {
+        instance_id: instance.id.clone(),
+    };
+    let payload = serde_json::to_string(&request)?;
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Cdb,
+        action: "DescribeDBSecurityGroups",
+        payload,
+        region: instance.region.clone(),
+        secret: secret.borrow(),
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeDBSecurityGroupsResponse>>(&result)
+}
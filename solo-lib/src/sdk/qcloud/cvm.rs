@@ -17,6 +17,15 @@ pub struct SecurityGroup {
     pub region: String,
 }
 
+/// CVM instance
+///
+/// Used to look up the security groups attached to an instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Instance {
+    pub id: String,
+    pub region: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityGroupPolicySet {
     #[serde(rename = "Version", skip_serializing_if = "String::is_empty")]
@@ -122,6 +131,68 @@ pub struct ReplaceSecurityGroupPoliciesRequest {
     pub security_group_policy_set: SecurityGroupPolicySet,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DescribeInstancesRequest {
+    #[serde(rename = "InstanceIds")]
+    pub instance_ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DescribeInstancesResponse {
+    #[serde(rename = "InstanceSet")]
+    pub instance_set: Vec<InstanceInfo>,
+    #[serde(rename = "TotalCount")]
+    pub total_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstanceInfo {
+    #[serde(rename = "InstanceId")]
+    pub instance_id: String,
+    #[serde(rename = "SecurityGroupIds", default)]
+    pub security_group_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Filter {
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Values")]
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DescribeSecurityGroupsRequest {
+    #[serde(rename = "Filters", skip_serializing_if = "Vec::is_empty")]
+    pub filters: Vec<Filter>,
+    #[serde(rename = "Offset")]
+    pub offset: String,
+    #[serde(rename = "Limit")]
+    pub limit: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DescribeSecurityGroupsResponse {
+    #[serde(rename = "SecurityGroupSet")]
+    pub security_group_set: Vec<SecurityGroupInfo>,
+    #[serde(rename = "TotalCount")]
+    pub total_count: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityGroupInfo {
+    #[serde(rename = "SecurityGroupId")]
+    pub security_group_id: String,
+    #[serde(rename = "SecurityGroupName", default)]
+    pub security_group_name: String,
+}
+
+/// Returns whether the given ID is a CVM instance ID (`ins-…`) rather than
+/// a security group ID (`sg-…`).
+pub fn is_instance_id(id: &str) -> bool {
+    id.starts_with("ins-")
+}
+
 /// ### Solo GO! - Main function
 ///
 /// Start to modify the security group rules.
@@ -223,6 +294,78 @@ pub async fn list_rules(
     )
 }
 
+/// ### SDK Implementation DescribeInstances
+///
+/// Looks up the instance, including the IDs of its attached security
+/// groups.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn describe_instance(
+    client: &Client,
+    instance: impl Borrow<Instance>,
+    secret: impl Borrow<Secret>,
+) -> Result<CommonResponse<DescribeInstancesResponse>> {
+    let instance = instance.borrow();
+
+    let request = DescribeInstancesRequest {
+        instance_ids: vec![instance.id.clone()],
+    };
+    let payload = serde_json::to_string(&request)?;
+    let basic_request = BasicRequest {
+        machine_type: MachineType::CvmInstance,
+        action: "DescribeInstances",
+        payload,
+        region: instance.region.clone(),
+        secret: secret.borrow(),
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeInstancesResponse>>(&result)
+}
+
+/// ### SDK Implementation DescribeSecurityGroups
+///
+/// Lists one page of the security groups in a region whose tags match all
+/// of the given `(key, value)` pairs, starting at `offset`.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_security_groups(
+    client: &Client,
+    region: &str,
+    secret: impl Borrow<Secret>,
+    tags: &[(String, String)],
+    offset: usize,
+) -> Result<CommonResponse<DescribeSecurityGroupsResponse>> {
+    let request = DescribeSecurityGroupsRequest {
+        filters: tags
+            .iter()
+            .map(|(key, value)| Filter {
+                name: format!("tag:{key}"),
+                values: vec![value.clone()],
+            })
+            .collect(),
+        offset: offset.to_string(),
+        limit: "100".to_string(),
+    };
+    let payload = serde_json::to_string(&request)?;
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Cvm,
+        action: "DescribeSecurityGroups",
+        payload,
+        region: region.to_string(),
+        secret: secret.borrow(),
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeSecurityGroupsResponse>>(&result)
+}
+
 /// ### SDK Process CompareSecurityGroupPolicies
 ///
 /// Note that this function is a single step of solo. Use it only if you
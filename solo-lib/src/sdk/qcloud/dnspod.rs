@@ -0,0 +1,268 @@
+//! # Qcloud DNSPod
+//!
+//! Begin with the [`go`] function
+//!
+//! `A` records are set to the current IPv4 address and `AAAA` records to
+//! the current IPv6 address.
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{
+    BasicRequest, CommonResponse, MachineType, Secret, parse_response,
+    request_builder,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Domain {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DescribeRecordListRequest {
+    #[serde(rename = "Domain")]
+    domain: String,
+    #[serde(rename = "Offset")]
+    offset: usize,
+    #[serde(rename = "Limit")]
+    limit: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DescribeRecordListResponse {
+    #[serde(rename = "RecordList", default)]
+    pub record_list: Vec<Record>,
+    #[serde(rename = "RecordCountInfo")]
+    pub record_count_info: RecordCountInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordCountInfo {
+    #[serde(rename = "TotalCount")]
+    pub total_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    #[serde(rename = "RecordId")]
+    pub record_id: u64,
+    /// Subdomain, `@` for the domain itself
+    #[serde(rename = "Name")]
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub record_type: String,
+    #[serde(rename = "Value")]
+    pub value: String,
+    #[serde(rename = "Line")]
+    pub line: String,
+    #[serde(rename = "LineId", default)]
+    pub line_id: String,
+    #[serde(rename = "TTL", default)]
+    pub ttl: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct ModifyRecordRequest<'a> {
+    #[serde(rename = "Domain")]
+    domain: &'a str,
+    #[serde(rename = "RecordId")]
+    record_id: u64,
+    #[serde(rename = "SubDomain")]
+    sub_domain: &'a str,
+    #[serde(rename = "RecordType")]
+    record_type: &'a str,
+    #[serde(rename = "RecordLine")]
+    record_line: &'a str,
+    #[serde(rename = "RecordLineId", skip_serializing_if = "str::is_empty")]
+    record_line_id: &'a str,
+    #[serde(rename = "Value")]
+    value: &'a str,
+    #[serde(rename = "TTL", skip_serializing_if = "is_zero")]
+    ttl: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModifyRecordResponse {
+    #[serde(rename = "RecordId")]
+    pub record_id: u64,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the DNS records.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::qcloud::{
+///     Secret,
+///     dnspod::{Domain, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         secret_id: "secret_id".to_string(),
+///         secret_key: "secret_key".to_string(),
+///     };
+///     let domain = Domain {
+///         name: "example.com".to_string(),
+///     };
+///     let _result = go(
+///         &client,
+///         &domain,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["home".to_string(), "@".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    domain: impl Borrow<Domain>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_records: &[String],
+) -> Result<()> {
+    let records = list_rules(client, domain.borrow(), secret.borrow()).await?;
+    let (records, require_update) =
+        compare_rules(&records, current_ipv4, current_ipv6, matched_records);
+    if require_update {
+        modify_rules(client, domain.borrow(), secret.borrow(), &records)
+            .await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation DescribeRecordList
+///
+/// Fetches every page of the records.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    domain: &Domain,
+    secret: &Secret,
+) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+    loop {
+        let request = DescribeRecordListRequest {
+            domain: domain.name.clone(),
+            offset: records.len(),
+            limit: 3000,
+        };
+        let payload = serde_json::to_string(&request)?;
+        let basic_request = BasicRequest {
+            machine_type: MachineType::Dnspod,
+            action: "DescribeRecordList",
+            payload,
+            region: String::new(),
+            secret,
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        let response = parse_response::<
+            CommonResponse<DescribeRecordListResponse>,
+        >(&result)?
+        .response
+        .data;
+        let page_len = response.record_list.len();
+        records.extend(response.record_list);
+        if page_len == 0
+            || records.len() >= response.record_count_info.total_count
+        {
+            break;
+        }
+    }
+    Ok(records)
+}
+
+/// ### SDK Process CompareRecords
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    records: &[Record],
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_records: &[String],
+) -> (Vec<Record>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_records.iter().map(|s| s.as_str()).collect();
+
+    let mut records_to_be_modified = Vec::new();
+    for record in records {
+        if !matched_set.contains(record.name.as_str()) {
+            continue;
+        }
+        let current = match record.record_type.as_str() {
+            "A" => current_ipv4,
+            "AAAA" => current_ipv6,
+            _ => continue,
+        };
+        if !current.is_empty() && record.value != current {
+            records_to_be_modified.push(Record {
+                value: current.to_string(),
+                ..record.clone()
+            });
+        }
+    }
+
+    let require_update = !records_to_be_modified.is_empty();
+    (records_to_be_modified, require_update)
+}
+
+/// ### SDK Implementation ModifyRecord
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    domain: &Domain,
+    secret: &Secret,
+    records: &[Record],
+) -> Result<()> {
+    for record in records {
+        let request = ModifyRecordRequest {
+            domain: &domain.name,
+            record_id: record.record_id,
+            sub_domain: &record.name,
+            record_type: &record.record_type,
+            record_line: &record.line,
+            record_line_id: &record.line_id,
+            value: &record.value,
+            ttl: record.ttl,
+        };
+        let payload = serde_json::to_string(&request)?;
+        let basic_request = BasicRequest {
+            machine_type: MachineType::Dnspod,
+            action: "ModifyRecord",
+            payload,
+            region: String::new(),
+            secret,
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        parse_response::<CommonResponse<ModifyRecordResponse>>(&result)?;
+    }
+    Ok(())
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
@@ -3,8 +3,14 @@
 //! Supports:
 //! - CVM [`cvm`]
 //! - Lighthouse [`lighthouse`]
+//! - IP Address Template [`template`]
+//! - TencentDB for MySQL [`cdb`]
+//! - DNSPod [`dnspod`]
 
+pub mod cdb;
 pub mod cvm;
+pub mod dnspod;
 pub mod lighthouse;
+pub mod template;
 mod util;
 pub use util::*;
@@ -0,0 +1,488 @@
+//! # Qcloud IP Address Template
+//!
+//! Begin with the [`go`] function
+//!
+//! An address template can be referenced by many security groups, updating
+//! it once updates all of them. Address template groups are supported as
+//! well, in which case every template of the group is updated.
+
+use std::{borrow::Borrow, collections::HashSet, result::Result::Ok};
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    cvm::Filter,
+    util::{
+        BasicRequest, CommonResponse, Empty, MachineType, Secret,
+        parse_response, request_builder,
+    },
+};
+use crate::{SdkError, util::family_addresses};
+
+/// Address template or address template group
+///
+/// `id` may either be an ID (`ipm-…` for templates, `ipmg-…` for groups) or
+/// the name of a template or group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub id: String,
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressTemplate {
+    #[serde(rename = "AddressTemplateId")]
+    pub address_template_id: String,
+    #[serde(rename = "AddressTemplateName")]
+    pub address_template_name: String,
+    #[serde(rename = "AddressSet", default)]
+    pub address_set: Vec<String>,
+    #[serde(rename = "AddressExtraSet", default)]
+    pub address_extra_set: Vec<AddressInfo>,
+}
+
+impl AddressTemplate {
+    /// Returns the entries of the template, along with their descriptions.
+    pub fn entries(&self) -> Vec<AddressInfo> {
+        if self.address_extra_set.is_empty() {
+            self.address_set
+                .iter()
+                .map(|address| AddressInfo {
+                    address: address.clone(),
+                    description: String::new(),
+                })
+                .collect()
+        } else {
+            self.address_extra_set.clone()
+        }
+    }
+}
+
+/// Description of the entries holding the extra addresses
+pub const EXTRA_DESCRIPTION: &str = "solo-extra";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AddressInfo {
+    #[serde(rename = "Address")]
+    pub address: String,
+    #[serde(
+        rename = "Description",
+        default,
+        skip_serializing_if = "String::is_empty"
+    )]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressTemplateGroup {
+    #[serde(rename = "AddressTemplateGroupId")]
+    pub address_template_group_id: String,
+    #[serde(rename = "AddressTemplateGroupName")]
+    pub address_template_group_name: String,
+    #[serde(rename = "AddressTemplateIdSet", default)]
+    pub address_template_id_set: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DescribeRequest {
+    #[serde(rename = "Filters", skip_serializing_if = "Vec::is_empty")]
+    filters: Vec<Filter>,
+    #[serde(rename = "Offset")]
+    offset: String,
+    #[serde(rename = "Limit")]
+    limit: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DescribeAddressTemplatesResponse {
+    #[serde(rename = "AddressTemplateSet")]
+    pub address_template_set: Vec<AddressTemplate>,
+    #[serde(rename = "TotalCount")]
+    pub total_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DescribeAddressTemplateGroupsResponse {
+    #[serde(rename = "AddressTemplateGroupSet")]
+    pub address_template_group_set: Vec<AddressTemplateGroup>,
+    #[serde(rename = "TotalCount")]
+    pub total_count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ModifyAddressTemplateAttributeRequest {
+    #[serde(rename = "AddressTemplateId")]
+    address_template_id: String,
+    #[serde(rename = "AddressesExtra")]
+    addresses_extra: Vec<AddressInfo>,
+}
+
+/// Returns whether the given ID is an address template ID (`ipm-…`).
+pub fn is_template_id(id: &str) -> bool {
+    id.starts_with("ipm-")
+}
+
+/// Returns whether the given ID is an address template group ID
+/// (`ipmg-…`).
+pub fn is_group_id(id: &str) -> bool {
+    id.starts_with("ipmg-")
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the address template entries.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// Entries whose description is in `matched_descriptions` are replaced with
+/// the current address of the same family, and `extra_addresses` are kept
+/// in entries described as [`EXTRA_DESCRIPTION`]. If `matched_descriptions`
+/// is empty, the whole template is replaced with the current addresses and
+/// `extra_addresses`.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::qcloud::{
+///     Secret,
+///     template::{Template, go},
+/// };
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         secret_id: "secret_id".to_string(),
+///         secret_key: "secret_key".to_string(),
+///     };
+///     let template = Template {
+///         id: "address_template_id".to_string(),
+///         region: "address_template_region".to_string(),
+///     };
+///     let _result = go(
+///         &client,
+///         &template,
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["203.0.113.0/24".to_string()],
+///         &[
+///             "entry_description_one".to_string(),
+///             "entry_description_two".to_string(),
+///         ],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    template: impl Borrow<Template>,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_descriptions: &[String],
+) -> Result<()> {
+    let templates =
+        find_templates(client, template.borrow(), secret.borrow()).await?;
+    for address_template in templates {
+        let (addresses, require_update) = compare_rules(
+            &address_template,
+            current_ipv4,
+            current_ipv6,
+            extra_addresses,
+            matched_descriptions,
+        );
+        if require_update {
+            modify_rules(
+                client,
+                template.borrow(),
+                secret.borrow(),
+                &address_template.address_template_id,
+                &addresses,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// ### SDK Process FindAddressTemplates
+///
+/// Looks up the address templates `template` refers to. A group resolves to
+/// all of its member templates.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn find_templates(
+    client: &Client,
+    template: impl Borrow<Template>,
+    secret: impl Borrow<Secret>,
+) -> Result<Vec<AddressTemplate>> {
+    let template = template.borrow();
+    let secret = secret.borrow();
+
+    let group_filter = if is_group_id(&template.id) {
+        Some(filter(
+            "address-template-group-id",
+            vec![template.id.clone()],
+        ))
+    } else if is_template_id(&template.id) {
+        None
+    } else {
+        let response = list_templates(
+            client,
+            template,
+            secret,
+            &[filter("address-template-name", vec![template.id.clone()])],
+        )
+        .await?;
+        let templates = response.response.data.address_template_set;
+        if templates.is_empty() {
+            Some(filter(
+                "address-template-group-name",
+                vec![template.id.clone()],
+            ))
+        } else {
+            return Ok(templates);
+        }
+    };
+
+    let template_ids = if let Some(group_filter) = group_filter {
+        let response =
+            list_template_groups(client, template, secret, &[group_filter])
+                .await?;
+        let request_id = response.response.request_id;
+        let Some(group) =
+            response.response.data.address_template_group_set.first()
+        else {
+            return Err(not_found(request_id, &template.id).into());
+        };
+        group.address_template_id_set.clone()
+    } else {
+        vec![template.id.clone()]
+    };
+    if template_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let response = list_templates(
+        client,
+        template,
+        secret,
+        &[filter("address-template-id", template_ids)],
+    )
+    .await?;
+    let templates = response.response.data.address_template_set;
+    if templates.is_empty() {
+        return Err(
+            not_found(response.response.request_id, &template.id).into()
+        );
+    }
+    Ok(templates)
+}
+
+/// ### SDK Implementation DescribeAddressTemplates
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_templates(
+    client: &Client,
+    template: impl Borrow<Template>,
+    secret: impl Borrow<Secret>,
+    filters: &[Filter],
+) -> Result<CommonResponse<DescribeAddressTemplatesResponse>> {
+    let request = DescribeRequest {
+        filters: filters.to_vec(),
+        offset: "0".to_string(),
+        limit: "100".to_string(),
+    };
+    let payload = serde_json::to_string(&request)?;
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Cvm,
+        action: "DescribeAddressTemplates",
+        payload,
+        region: template.borrow().region.clone(),
+        secret: secret.borrow(),
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeAddressTemplatesResponse>>(&result)
+}
+
+/// ### SDK Implementation DescribeAddressTemplateGroups
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_template_groups(
+    client: &Client,
+    template: impl Borrow<Template>,
+    secret: impl Borrow<Secret>,
+    filters: &[Filter],
+) -> Result<CommonResponse<DescribeAddressTemplateGroupsResponse>> {
+    let request = DescribeRequest {
+        filters: filters.to_vec(),
+        offset: "0".to_string(),
+        limit: "100".to_string(),
+    };
+    let payload = serde_json::to_string(&request)?;
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Cvm,
+        action: "DescribeAddressTemplateGroups",
+        payload,
+        region: template.borrow().region.clone(),
+        secret: secret.borrow(),
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<DescribeAddressTemplateGroupsResponse>>(
+        &result,
+    )
+}
+
+/// ### SDK Process CompareAddressTemplate
+///
+/// Without matched descriptions, the template is replaced by the current
+/// and the extra addresses. Otherwise the matched entries are set to the
+/// current address of their family, and the extra addresses are listed in
+/// entries described as [`EXTRA_DESCRIPTION`].
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    address_template: &AddressTemplate,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    matched_descriptions: &[String],
+) -> (Vec<AddressInfo>, bool) {
+    let entries = address_template.entries();
+
+    if matched_descriptions.is_empty() {
+        let addresses: Vec<AddressInfo> =
+            family_addresses(current_ipv4, extra_addresses, false, false)
+                .into_iter()
+                .chain(family_addresses(
+                    current_ipv6,
+                    extra_addresses,
+                    true,
+                    false,
+                ))
+                .map(|address| AddressInfo {
+                    address,
+                    description: String::new(),
+                })
+                .collect();
+        let current: HashSet<&str> =
+            addresses.iter().map(|a| a.address.as_str()).collect();
+        let existing: HashSet<&str> =
+            entries.iter().map(|a| a.address.as_str()).collect();
+        let require_update = current != existing;
+        return (addresses, require_update);
+    }
+
+    let matched_set: HashSet<&str> =
+        matched_descriptions.iter().map(|s| s.as_str()).collect();
+
+    let mut require_update = false;
+    let mut addresses: Vec<AddressInfo> = entries
+        .into_iter()
+        .map(|mut entry| {
+            if matched_set.contains(entry.description.as_str()) {
+                let current = if entry.address.contains(':') {
+                    current_ipv6
+                } else {
+                    current_ipv4
+                };
+                if !current.is_empty() && entry.address != current {
+                    entry.address = current.to_string();
+                    require_update = true;
+                }
+            }
+            entry
+        })
+        .collect();
+
+    // Extra addresses are kept in entries of their own description, which
+    // are replaced as a whole
+    let extra: Vec<&String> = extra_addresses
+        .iter()
+        .filter(|address| {
+            !addresses.iter().any(|entry| {
+                entry.address == **address
+                    && entry.description != EXTRA_DESCRIPTION
+            })
+        })
+        .collect();
+    let listed: Vec<&String> = addresses
+        .iter()
+        .filter(|entry| entry.description == EXTRA_DESCRIPTION)
+        .map(|entry| &entry.address)
+        .collect();
+    if listed != extra {
+        let extra: Vec<AddressInfo> = extra
+            .into_iter()
+            .map(|address| AddressInfo {
+                address: address.clone(),
+                description: EXTRA_DESCRIPTION.to_string(),
+            })
+            .collect();
+        addresses.retain(|entry| entry.description != EXTRA_DESCRIPTION);
+        addresses.extend(extra);
+        require_update = true;
+    }
+
+    (addresses, require_update)
+}
+
+/// ### SDK Implementation ModifyAddressTemplateAttribute
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    template: impl Borrow<Template>,
+    secret: impl Borrow<Secret>,
+    address_template_id: &str,
+    addresses: &[AddressInfo],
+) -> Result<CommonResponse<Empty>> {
+    let request = ModifyAddressTemplateAttributeRequest {
+        address_template_id: address_template_id.to_string(),
+        addresses_extra: addresses.to_vec(),
+    };
+    let payload = serde_json::to_string(&request)?;
+    let basic_request = BasicRequest {
+        machine_type: MachineType::Cvm,
+        action: "ModifyAddressTemplateAttribute",
+        payload,
+        region: template.borrow().region.clone(),
+        secret: secret.borrow(),
+    };
+    let request = request_builder(client, basic_request)?;
+    let result = client.execute(request).await?;
+    let result = result.text().await?;
+
+    parse_response::<CommonResponse<Empty>>(&result)
+}
+
+fn filter(name: &str, values: Vec<String>) -> Filter {
+    Filter {
+        name: name.to_string(),
+        values,
+    }
+}
+
+fn not_found(request_id: String, id: &str) -> SdkError {
+    SdkError {
+        request_id,
+        code: "ResourceNotFound".to_string(),
+        message: format!("address template `{id}` not found"),
+    }
+}
@@ -29,6 +29,15 @@ pub enum MachineType {
 
     /// Cvm instance
     Cvm,
+
+    /// Cvm instance management, used to look up instance details
+    CvmInstance,
+
+    /// TencentDB for MySQL instance
+    Cdb,
+
+    /// DNSPod domain
+    Dnspod,
 }
 
 impl MachineType {
@@ -49,6 +58,24 @@ impl MachineType {
                 "2017-03-12",
                 "https://vpc.tencentcloudapi.com",
             ),
+            MachineType::CvmInstance => (
+                "cvm",
+                "cvm.tencentcloudapi.com",
+                "2017-03-12",
+                "https://cvm.tencentcloudapi.com",
+            ),
+            MachineType::Cdb => (
+                "cdb",
+                "cdb.tencentcloudapi.com",
+                "2017-03-20",
+                "https://cdb.tencentcloudapi.com",
+            ),
+            MachineType::Dnspod => (
+                "dnspod",
+                "dnspod.tencentcloudapi.com",
+                "2021-03-23",
+                "https://dnspod.tencentcloudapi.com",
+            ),
         }
     }
 }
@@ -157,7 +184,10 @@ pub(super) fn request_builder(
     headers.insert("X-TC-Action", basic_request.action.parse()?);
     headers.insert("X-TC-Timestamp", timestamp.to_string().parse()?);
     headers.insert("X-TC-Version", version.parse()?);
-    headers.insert("X-TC-Region", basic_request.region.parse()?);
+    // Global services such as DNSPod take no region
+    if !basic_request.region.is_empty() {
+        headers.insert("X-TC-Region", basic_request.region.parse()?);
+    }
 
     Ok(client
         .post(endpoint)
@@ -0,0 +1,230 @@
+//! # Vultr Firewall Groups
+//!
+//! Begin with the [`go`] function
+
+use std::{borrow::Borrow, collections::HashSet};
+
+use anyhow::Result;
+use http::Method;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::util::{BasicRequest, Secret, parse_response, request_builder};
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListFirewallRulesResponse {
+    pub firewall_rules: Vec<FirewallRule>,
+    #[serde(default)]
+    pub meta: Option<Meta>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Meta {
+    #[serde(default)]
+    pub links: Option<Links>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Links {
+    #[serde(default)]
+    pub next: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FirewallRule {
+    #[serde(default, skip_serializing)]
+    pub id: u64,
+    /// `v4` or `v6`
+    pub ip_type: String,
+    pub protocol: String,
+    pub subnet: String,
+    pub subnet_size: u8,
+    #[serde(default)]
+    pub port: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub source: String,
+    #[serde(default)]
+    pub notes: String,
+}
+
+/// ### Solo GO! - Main function
+///
+/// Start to modify the firewall group rules.
+///
+/// This function is a basic implementation of the SDK. It is recommended to use
+/// this function directly.
+///
+/// ### Example
+/// ```rust,no_run
+/// use solo_lib::sdk::vultr::{Secret, firewall::go};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let client = solo_lib::client::new();
+///     let secret = Secret {
+///         token: "api_key".to_string(),
+///         endpoint: None,
+///     };
+///     let _result = go(
+///         &client,
+///         "firewall_group_id",
+///         &secret,
+///         "current_ipv4",
+///         "current_ipv6",
+///         &["rule_notes".to_string()],
+///     )
+///     .await
+///     .unwrap();
+/// }
+/// ```
+pub async fn go(
+    client: &Client,
+    firewall_group_id: &str,
+    secret: impl Borrow<Secret>,
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_notes: &[String],
+) -> Result<()> {
+    let rules = list_rules(client, firewall_group_id, secret.borrow()).await?;
+    let (rules, require_update) =
+        compare_rules(&rules, current_ipv4, current_ipv6, matched_notes);
+    if require_update {
+        modify_rules(client, firewall_group_id, secret.borrow(), &rules)
+            .await?;
+    }
+    Ok(())
+}
+
+/// ### SDK Implementation List Firewall Rules
+///
+/// Fetches every page of the rules.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn list_rules(
+    client: &Client,
+    firewall_group_id: &str,
+    secret: &Secret,
+) -> Result<Vec<FirewallRule>> {
+    let mut rules = Vec::new();
+    let mut cursor = String::new();
+    loop {
+        let mut query = vec![("per_page", "500")];
+        if !cursor.is_empty() {
+            query.push(("cursor", cursor.as_str()));
+        }
+        let basic_request = BasicRequest {
+            method: Method::GET,
+            path: format!("/firewalls/{firewall_group_id}/rules"),
+            query: &query,
+            payload: String::new(),
+            secret,
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+
+        let response = parse_response::<ListFirewallRulesResponse>(&result)?;
+        rules.extend(response.firewall_rules);
+        match response.meta.and_then(|meta| meta.links) {
+            Some(links) if !links.next.is_empty() => cursor = links.next,
+            _ => break,
+        }
+    }
+    Ok(rules)
+}
+
+/// ### SDK Process CompareFirewallRules
+///
+/// Rules whose notes are in `matched_notes` are set to the current address
+/// of their IP type, which may be a CIDR block.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub fn compare_rules(
+    rules: &[FirewallRule],
+    current_ipv4: &str,
+    current_ipv6: &str,
+    matched_notes: &[String],
+) -> (Vec<FirewallRule>, bool) {
+    let matched_set: HashSet<&str> =
+        matched_notes.iter().map(|s| s.as_str()).collect();
+
+    let mut rules_to_be_modified = Vec::new();
+    for rule in rules {
+        if !matched_set.contains(rule.notes.as_str()) {
+            continue;
+        }
+        let (current, subnet_size) = match rule.ip_type.as_str() {
+            "v4" => (current_ipv4, 32),
+            "v6" => (current_ipv6, 128),
+            _ => continue,
+        };
+        // The address may be a CIDR block
+        let (current, subnet_size) = current
+            .split_once('/')
+            .and_then(|(subnet, size)| Some((subnet, size.parse().ok()?)))
+            .unwrap_or((current, subnet_size));
+        if !current.is_empty()
+            && (rule.subnet != current || rule.subnet_size != subnet_size)
+        {
+            rules_to_be_modified.push(FirewallRule {
+                subnet: current.to_string(),
+                subnet_size,
+                ..rule.clone()
+            });
+        }
+    }
+
+    let require_update = !rules_to_be_modified.is_empty();
+    (rules_to_be_modified, require_update)
+}
+
+/// ### SDK Implementation Create and Delete Firewall Rules
+///
+/// Firewall rules cannot be updated, so a rule with the new address is
+/// created before the old rule is deleted, the address stays allowed if the
+/// creation fails. Rules collapsing to the same rule are created once.
+///
+/// Note that this function is a single step of solo. Use it only if you
+/// would like to hook.
+pub async fn modify_rules(
+    client: &Client,
+    firewall_group_id: &str,
+    secret: &Secret,
+    rules: &[FirewallRule],
+) -> Result<()> {
+    let mut created = HashSet::new();
+    for rule in rules {
+        let payload = serde_json::to_string(rule)?;
+        if created.insert(payload.clone()) {
+            let basic_request = BasicRequest {
+                method: Method::POST,
+                path: format!("/firewalls/{firewall_group_id}/rules"),
+                query: &[],
+                payload,
+                secret,
+            };
+            let request = request_builder(client, basic_request)?;
+            let result = client.execute(request).await?;
+            let result = result.text().await?;
+            parse_response::<serde_json::Value>(&result)?;
+        }
+
+        let basic_request = BasicRequest {
+            method: Method::DELETE,
+            path: format!("/firewalls/{firewall_group_id}/rules/{}", rule.id),
+            query: &[],
+            payload: String::new(),
+            secret,
+        };
+        let request = request_builder(client, basic_request)?;
+        let result = client.execute(request).await?;
+        let result = result.text().await?;
+        // A successful deletion has no body
+        if !result.is_empty() {
+            parse_response::<serde_json::Value>(&result)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,9 @@
+//! # Vultr SDK
+//!
+//! Supports:
+//! - Firewall Groups [`firewall`]
+
+pub mod firewall;
+
+mod util;
+pub use util::*;
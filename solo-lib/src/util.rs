@@ -22,3 +22,72 @@ pub(crate) fn hmac256(key: &[u8], message: &str) -> Result<Vec<u8>, String> {
     let signature = mac.finalize();
     Ok(signature.into_bytes().to_vec())
 }
+
+/// Replace the addresses of each family with the current address of that
+/// family and the extra addresses of the same family. An empty current
+/// address leaves its family untouched.
+///
+/// With `as_cidr`, single addresses are written as host blocks.
+pub(crate) fn replace_addresses(
+    addresses: &[String],
+    current_ipv4: &str,
+    current_ipv6: &str,
+    extra_addresses: &[String],
+    as_cidr: bool,
+) -> Vec<String> {
+    let mut replaced: Vec<String> = addresses
+        .iter()
+        .filter(|address| {
+            let is_ipv6 = address.contains(':');
+            (is_ipv6 && current_ipv6.is_empty())
+                || (!is_ipv6 && current_ipv4.is_empty())
+        })
+        .cloned()
+        .collect();
+    replaced.extend(family_addresses(
+        current_ipv4,
+        extra_addresses,
+        false,
+        as_cidr,
+    ));
+    replaced.extend(family_addresses(
+        current_ipv6,
+        extra_addresses,
+        true,
+        as_cidr,
+    ));
+    replaced
+}
+
+/// The current address followed by the extra addresses of its family,
+/// without duplicates. Empty if there is no current address.
+///
+/// With `as_cidr`, single addresses are written as host blocks.
+pub(crate) fn family_addresses(
+    current: &str,
+    extra_addresses: &[String],
+    is_ipv6: bool,
+    as_cidr: bool,
+) -> Vec<String> {
+    if current.is_empty() {
+        return Vec::new();
+    }
+    let prefix_len = if is_ipv6 { 128 } else { 32 };
+    let extra = extra_addresses
+        .iter()
+        .map(String::as_str)
+        .filter(|address| address.contains(':') == is_ipv6);
+
+    let mut addresses: Vec<String> = Vec::new();
+    for address in std::iter::once(current).chain(extra) {
+        let address = if as_cidr && !address.contains('/') {
+            format!("{address}/{prefix_len}")
+        } else {
+            address.to_string()
+        };
+        if !addresses.contains(&address) {
+            addresses.push(address);
+        }
+    }
+    addresses
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fs,
     io::{Write, stdout},
     time::Duration,
@@ -226,9 +227,19 @@ fn generate_example_config() -> String {
             name: t!("Server Name").to_string(),
             machine_type: MachineType::AliyunEcs,
             machine_id: t!("Server Instance ID").to_string(),
+            machine_ids: vec![],
+            tags: BTreeMap::new(),
             region: t!("Server Region").to_string(),
+            regions: vec![],
             secret_id: t!("Secret ID").to_string(),
             secret_key: t!("Secret Key").to_string(),
+            accounts: vec![],
+            endpoint: None,
+            proxied: None,
+            ttl: None,
+            kubeconfig: None,
+            file: None,
+            extra_addresses: Vec::new(),
             protocol: Protocol::V4,
             rules: vec![
                 t!("First Rule").to_string(),
@@ -239,6 +250,13 @@ fn generate_example_config() -> String {
         ip_provider: IpProvider::Embed(EmbedIpProvider::IpEcho),
         notifications: vec![],
         no_proxy: false,
+        allow_private_ip: false,
+        extra_addresses: Vec::new(),
+        uplinks: Vec::new(),
+        state: None,
+        grace_period: None,
+        address_history: None,
+        team: None,
     };
     toml::to_string_pretty(&config).unwrap()
 }
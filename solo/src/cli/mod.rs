@@ -1,6 +1,8 @@
 pub mod conf;
 mod conf_new;
 pub mod go;
+pub mod report;
+pub mod serve;
 pub mod util;
 pub mod version;
 
@@ -13,6 +15,8 @@ use crate::{
     cli::{
         conf::handle_conf_command,
         go::handle_go_command,
+        report::handle_report_command,
+        serve::handle_serve_command,
         util::{
             HELP_ARGS, HelpSubcommand, build_help_subcommands, print_error_info,
         },
@@ -26,6 +30,8 @@ pub enum CliAction {
     RunConfig(Vec<String>),
     ManageConfig(ManageConfigAction),
     Version(VersionAction),
+    Serve(String),
+    Report(ReportAction),
     ShowHelp(HelpInfo),
 }
 
@@ -39,10 +45,19 @@ pub enum VersionAction {
     Update,
 }
 
+/// Report the address of this member to a team server.
+pub struct ReportAction {
+    pub url: String,
+    pub ip: Option<String>,
+    pub token: String,
+}
+
 pub enum HelpInfo {
     Main,
     Go,
     Conf,
+    Serve,
+    Report,
     Version,
 }
 
@@ -61,6 +76,8 @@ pub fn parse() -> Option<CliAction> {
         "version" => handle_version_command(&args, args_quantity),
         "conf" => handle_conf_command(&args, args_quantity),
         "go" => handle_go_command(&args, args_quantity),
+        "serve" => handle_serve_command(&args, args_quantity),
+        "report" => handle_report_command(&args, args_quantity),
         _ => handle_unknown_command(),
     }
 }
@@ -113,6 +130,16 @@ pub fn show_help() {
             additional_arg: None,
             description: t!("Manage configuration files"),
         },
+        HelpSubcommand {
+            name: "serve",
+            additional_arg: Some(t!("<config name>")),
+            description: t!("Collect the addresses of team members"),
+        },
+        HelpSubcommand {
+            name: "report",
+            additional_arg: Some(t!("<server url>")),
+            description: t!("Report the address of this machine to a team"),
+        },
         HelpSubcommand {
             name: "version",
             additional_arg: None,
@@ -0,0 +1,129 @@
+use std::env;
+
+use cnxt::Colorize as _;
+use rust_i18n::t;
+
+use crate::{
+    cli::{
+        CliAction, HelpInfo, ReportAction,
+        util::{
+            HELP_ARGS, HelpSubcommand, build_help_subcommands, print_error_info,
+        },
+    },
+    consts::EXE_NAME,
+};
+
+/// Environment variable holding the token if `--token` is not given.
+const TOKEN_ENV: &str = "SOLO_TOKEN";
+
+// Handle the `report` command
+pub fn handle_report_command(
+    args: &[String],
+    args_quantity: usize,
+) -> Option<CliAction> {
+    if args_quantity == 2 || HELP_ARGS.contains(&args[2].as_str()) {
+        return Some(CliAction::ShowHelp(HelpInfo::Report));
+    }
+
+    let url = args[2].clone();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        print_error_info(
+            &[2],
+            &t!("This is not a valid URL"),
+            Some(&t!("The URL starts with http:// or https://")),
+        );
+        return None;
+    }
+
+    let mut ip = None;
+    let mut token = None;
+    let mut i = 3;
+    while i < args_quantity {
+        let value = args.get(i + 1).cloned();
+        match args[i].as_str() {
+            "--ip" if value.is_some() => ip = value,
+            "--token" if value.is_some() => token = value,
+            "--ip" | "--token" => {
+                print_error_info(&[i], &t!("This option needs a value"), None);
+                return None;
+            }
+            _ => {
+                print_error_info(
+                    &[i],
+                    &t!("This is not a valid option"),
+                    Some(&t!(
+                        "Type %{cmd} for help",
+                        cmd = format!("`{} report help`", *EXE_NAME)
+                    )),
+                );
+                return None;
+            }
+        }
+        i += 2;
+    }
+
+    let Some(token) = token.or_else(|| env::var(TOKEN_ENV).ok()) else {
+        print_error_info(
+            &[2],
+            &t!("A token is required to report"),
+            Some(&t!(
+                "Pass it with %{option} or set %{env}",
+                option = "--token",
+                env = TOKEN_ENV
+            )),
+        );
+        return None;
+    };
+
+    Some(CliAction::Report(ReportAction { url, ip, token }))
+}
+
+// Show the help message for the `report` command
+pub fn show_help() {
+    let mut help: Vec<String> = Vec::new();
+    help.push(format!(
+        "{} {} {} {} {}\n",
+        t!("Usage:").bright_green(),
+        EXE_NAME.bright_cyan(),
+        "report".bright_yellow(),
+        t!("<server url>").bright_blue(),
+        t!("[options]").bright_blue()
+    ));
+    help.push(format!("{}", t!("Available options:").bright_green()));
+    let options: Vec<HelpSubcommand> = vec![
+        HelpSubcommand {
+            name: "--token",
+            additional_arg: Some(t!("<token>")),
+            description: t!(
+                "Token of the member, read from %{env} if not given",
+                env = TOKEN_ENV
+            ),
+        },
+        HelpSubcommand {
+            name: "--ip",
+            additional_arg: Some(t!("<address>")),
+            description: t!(
+                "Address to allow, the server uses the address the report \
+                 comes from if not given"
+            ),
+        },
+    ];
+    help.extend(build_help_subcommands(options));
+
+    help.push(format!("\n{}:", t!("Examples").bright_green()));
+    help.push(format!(
+        "   {} {} {}",
+        EXE_NAME.bright_cyan(),
+        "report".bright_magenta(),
+        "https://solo.example.com".bright_yellow()
+    ));
+    help.push(format!(
+        "   {}",
+        t!("Report the address of this machine to the team server")
+            .bright_magenta()
+    ));
+
+    for line in help {
+        println!("{line}");
+    }
+}
@@ -0,0 +1,79 @@
+use cnxt::Colorize as _;
+use rust_i18n::t;
+
+use crate::{
+    cli::{
+        CliAction, HelpInfo,
+        util::{HELP_ARGS, print_error_info},
+    },
+    config::CONFIG_LIST_NAMES,
+    consts::EXE_NAME,
+};
+
+// Handle the `serve` command
+pub fn handle_serve_command(
+    args: &[String],
+    args_quantity: usize,
+) -> Option<CliAction> {
+    match args_quantity {
+        2 => Some(CliAction::ShowHelp(HelpInfo::Serve)),
+        3 => match args[2].as_str() {
+            arg if HELP_ARGS.contains(&arg) => {
+                Some(CliAction::ShowHelp(HelpInfo::Serve))
+            }
+            config if CONFIG_LIST_NAMES.iter().any(|name| name == config) => {
+                Some(CliAction::Serve(config.to_string()))
+            }
+            _ => {
+                print_error_info(
+                    &[2],
+                    &t!("Specified configuration not found"),
+                    Some(&t!(
+                        "Please check if the configuration name is correct"
+                    )),
+                );
+                None
+            }
+        },
+        _ => {
+            print_error_info(
+                &[3],
+                &t!("This command does not support more parameters"),
+                Some(&t!("Remove extra parameters and try again")),
+            );
+            None
+        }
+    }
+}
+
+// Show the help message for the `serve` command
+pub fn show_help() {
+    let mut help: Vec<String> = Vec::new();
+    help.push(format!(
+        "{} {} {} {}\n",
+        t!("Usage:").bright_green(),
+        EXE_NAME.bright_cyan(),
+        "serve".bright_yellow(),
+        t!("<config name>").bright_blue()
+    ));
+    help.push(format!(
+        "{}\n",
+        t!("Collect the addresses reported by the team members of the \
+             configuration and keep one rule per member on its servers")
+    ));
+    help.push(format!("{}:", t!("Examples").bright_green()));
+    help.push(format!(
+        "   {} {} {}",
+        EXE_NAME.bright_cyan(),
+        "serve".bright_magenta(),
+        "team".bright_yellow()
+    ));
+    help.push(format!(
+        "   {}",
+        t!("Serve the team of the configuration named `team`").bright_magenta()
+    ));
+
+    for line in help {
+        println!("{line}");
+    }
+}
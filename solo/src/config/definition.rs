@@ -1,12 +1,55 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::ipfetcher::{IpProvider, Protocol};
 
-pub const MACHINE_TYPES_WITH_OPTIONAL_SECRET_ID: &[MachineType] =
-    &[MachineType::RainyunRcs];
+pub const MACHINE_TYPES_WITH_OPTIONAL_SECRET_ID: &[MachineType] = &[
+    MachineType::RainyunRcs,
+    MachineType::CloudflareList,
+    MachineType::CloudflareAccessRule,
+    MachineType::CloudflareDns,
+    MachineType::DigitaloceanFirewall,
+    MachineType::HetznerFirewall,
+    MachineType::VultrFirewall,
+    MachineType::KubernetesNetworkPolicy,
+    MachineType::KubernetesIngress,
+];
+
+pub const MACHINE_TYPES_WITH_OPTIONAL_REGION_ID: &[MachineType] = &[
+    MachineType::RainyunRcs,
+    MachineType::QcloudDnspod,
+    MachineType::AliyunCloudfw,
+    MachineType::AliyunDns,
+    MachineType::CloudflareAccessRule,
+    MachineType::CloudflareDns,
+    MachineType::DigitaloceanFirewall,
+    MachineType::HetznerFirewall,
+    MachineType::VultrFirewall,
+    MachineType::KubernetesNetworkPolicy,
+    MachineType::KubernetesIngress,
+    MachineType::File,
+];
+
+pub const MACHINE_TYPES_WITHOUT_SECRET: &[MachineType] = &[MachineType::File];
 
-pub const MACHINE_TYPES_WITH_OPTIONAL_REGION_ID: &[MachineType] =
-    &[MachineType::RainyunRcs];
+/// Firewall rules of these machine types hold a single address, so extra
+/// and further fetched addresses are applied to sibling rules.
+pub const MACHINE_TYPES_WITH_SINGLE_ADDRESS_RULES: &[MachineType] = &[
+    MachineType::QcloudCvm,
+    MachineType::QcloudLighthouse,
+    MachineType::QcloudCdb,
+    MachineType::AliyunEcs,
+    MachineType::AliyunSas,
+    MachineType::AliyunPrefixList,
+    MachineType::RainyunRcs,
+    MachineType::CloudflareList,
+    MachineType::CloudflareAccessRule,
+    MachineType::VultrFirewall,
+];
+
+pub const MACHINE_TYPES_WITH_TAG_SELECTOR: &[MachineType] =
+    &[MachineType::QcloudCvm, MachineType::AliyunEcs];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConfigFile {
@@ -19,9 +62,35 @@ pub struct ConfigFile {
 pub enum MachineType {
     QcloudCvm,
     QcloudLighthouse,
+    QcloudAddressTemplate,
+    QcloudCdb,
+    QcloudDnspod,
     AliyunEcs,
     AliyunSas,
+    AliyunPrefixList,
+    AliyunRds,
+    AliyunRedis,
+    AliyunCloudfw,
+    AliyunDns,
     RainyunRcs,
+    CloudflareList,
+    CloudflareAccessRule,
+    CloudflareDns,
+    DigitaloceanFirewall,
+    HetznerFirewall,
+    VultrFirewall,
+    KubernetesNetworkPolicy,
+    KubernetesIngress,
+    File,
+    /// An external plugin, see the plugin protocol of solo.
+    Plugin {
+        /// Command spawning the plugin, run by the shell.
+        command: String,
+        /// Time in seconds a single call of the plugin may take, defaults
+        /// to 60.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        timeout: Option<u64>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +108,109 @@ pub struct Config {
 
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub no_proxy: bool,
+    /// Accept private, loopback, link-local, CGNAT and unspecified addresses
+    /// returned by the IP provider.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow_private_ip: bool,
+    /// IP addresses or CIDR blocks allowed by every server, see
+    /// [`Server::extra_addresses`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_addresses: Vec<String>,
+    /// Local addresses or interface names the IP is fetched through, one
+    /// fetch per uplink. The addresses of every uplink are allowed, the
+    /// first one as the current address. The others are allowed like extra
+    /// ones, or by the sibling rules `<rule>-alt-N` where rules hold a
+    /// single address.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub uplinks: Vec<String>,
+    /// Remember the addresses applied to each server, and skip the servers
+    /// whose addresses did not change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<StateOptions>,
+    /// Keep the previous addresses allowed for a while after the address
+    /// changes, so that sessions from them are not dropped at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grace_period: Option<GracePeriod>,
+    /// Keep the most recently seen addresses allowed, for machines roaming
+    /// between a few networks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_history: Option<AddressHistory>,
+    /// Options of `solo serve`, which applies the addresses reported by team
+    /// members instead of fetching one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<TeamOptions>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateOptions {
+    /// Path of the state file, defaults to `<config name>.state.json` next
+    /// to the configuration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// Contact a server anyway once it was skipped this many runs in a row,
+    /// so that rules changed by hand are corrected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconcile_runs: Option<u32>,
+    /// Contact a server anyway once it was last contacted this many hours
+    /// ago.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reconcile_hours: Option<u32>,
+}
+
+/// Previous addresses are allowed like extra addresses, in rules that hold
+/// more than one address. The machine types whose rules hold a single
+/// address need `previous_rules`. Previous addresses are kept in the state
+/// file, see [`StateOptions`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GracePeriod {
+    /// How long a previous address stays allowed once it was last seen.
+    pub seconds: u32,
+    /// Keep the latest previous address in sibling rules named
+    /// `<rule>-prev`, on the machine types whose rules hold a single
+    /// address. The sibling rules are not deleted once the grace period is
+    /// over, they hold the current address as well until the next change.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub previous_rules: bool,
+}
+
+/// Previous addresses are allowed like extra addresses, in rules that hold
+/// more than one address. The oldest seen address is evicted first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressHistory {
+    /// Number of distinct addresses of each IP version kept, the current one
+    /// included.
+    pub size: usize,
+    /// Also keep the previous addresses in sibling rules named `<rule>-1` to
+    /// `<rule>-<size - 1>`, on the machine types whose rules hold a single
+    /// address. An address keeps its sibling rule until it is evicted, the
+    /// rule then gets the next previous address.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub sibling_rules: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamOptions {
+    /// Address the server listens on, such as `0.0.0.0:8650`.
+    pub listen: String,
+    /// Header holding the address of the member when solo runs behind a
+    /// reverse proxy, such as `X-Forwarded-For`. Its first address is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address_header: Option<String>,
+    /// Forget the address of an IP version once a member has not reported
+    /// it for this many hours, defaults to 24. Members report each IP
+    /// version they use at least this often.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expire_hours: Option<u32>,
+    /// Each rule of the servers is applied to a member as
+    /// `<rule>-<member name>`.
+    pub members: Vec<TeamMember>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TeamMember {
+    pub name: String,
+    /// Token the member reports with, sent as a bearer token.
+    pub token: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,19 +218,76 @@ pub struct Server {
     pub name: String,
 
     pub machine_type: MachineType,
+    #[serde(default)]
     pub machine_id: String,
+    /// Additional machine IDs, each one is handled as a separate target.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub machine_ids: Vec<String>,
+    /// Select every resource carrying all of these tags.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
 
     #[serde(default)]
     pub region: String,
+    /// Additional regions, each one is handled as a separate target.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<String>,
 
     #[serde(default)]
     pub secret_id: String,
+    #[serde(default)]
     pub secret_key: String,
+    /// Additional accounts, each one is handled as a separate target.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<Account>,
+    /// Overrides the base URL of the provider's API.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Whether updated DNS records are proxied, used by Cloudflare DNS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxied: Option<bool>,
+    /// TTL of updated DNS records, used by Cloudflare DNS.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl: Option<u32>,
+    /// Path of the kubeconfig used by Kubernetes targets, the region
+    /// selects its context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kubeconfig: Option<String>,
+    /// Options of the local file target.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<FileOptions>,
+    /// IP addresses or CIDR blocks allowed next to the fetched addresses.
+    /// Where rules hold a single address, each of them is kept in a sibling
+    /// rule `<rule>-extra-N`, which must exist like the rule itself.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_addresses: Vec<String>,
 
     pub protocol: Protocol,
     pub rules: Vec<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileOptions {
+    /// Lines containing `{ip}` are repeated for every address, defaults to
+    /// `{ip}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// Number of previous addresses kept in the file.
+    #[serde(default)]
+    pub keep_previous: usize,
+    /// Command run after the file is changed, such as `nginx -s reload`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reload_command: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub name: String,
+    #[serde(default)]
+    pub secret_id: String,
+    pub secret_key: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 #[derive(Default)]
@@ -154,6 +383,13 @@ mod tests {
                 },
             }],
             no_proxy: false,
+            allow_private_ip: false,
+            extra_addresses: Vec::new(),
+            uplinks: Vec::new(),
+            state: None,
+            grace_period: None,
+            address_history: None,
+            team: None,
         };
 
         let config = toml::to_string(&config).unwrap();
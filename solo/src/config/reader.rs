@@ -1,4 +1,10 @@
-use std::{borrow::Cow, fs, path::Path, process::exit};
+use std::{
+    borrow::Cow,
+    fs,
+    net::{IpAddr, SocketAddr},
+    path::Path,
+    process::exit,
+};
 
 use anyhow::{Result, anyhow};
 use cnxt::Colorize as _;
@@ -6,10 +12,16 @@ use rust_i18n::t;
 use unicode_width::UnicodeWidthStr as _;
 
 use super::definition::{
-    Config, ConfigFile, MACHINE_TYPES_WITH_OPTIONAL_SECRET_ID,
+    AddressHistory, Config, ConfigFile, GracePeriod,
+    MACHINE_TYPES_WITH_OPTIONAL_SECRET_ID,
+    MACHINE_TYPES_WITH_SINGLE_ADDRESS_RULES, MachineType, Server, TeamOptions,
 };
 use crate::config::{
-    definition::MACHINE_TYPES_WITH_OPTIONAL_REGION_ID, get_config_path,
+    definition::{
+        MACHINE_TYPES_WITH_OPTIONAL_REGION_ID, MACHINE_TYPES_WITH_TAG_SELECTOR,
+        MACHINE_TYPES_WITHOUT_SECRET,
+    },
+    get_config_path,
 };
 
 pub fn get_config_list() -> Vec<ConfigFile> {
@@ -65,49 +77,15 @@ pub fn process_config(config: Vec<String>) -> Result<Vec<Config>> {
             exit(1);
         })?;
 
+        check_extra_addresses(&config.extra_addresses);
+        if let Some(history) = &config.address_history {
+            check_address_history(history);
+        }
+        if let Some(team) = &config.team {
+            check_team(team);
+        }
         for server in &config.servers {
-            if server.secret_id.is_empty() {
-                if MACHINE_TYPES_WITH_OPTIONAL_SECRET_ID
-                    .contains(&server.machine_type)
-                {
-                    continue;
-                }
-                eprintln!(
-                    "{}",
-                    t!("Configuration file contains errors").bright_red()
-                );
-                eprintln!(
-                    "{}",
-                    t!(
-                        "Server %{name}'s secret_id cannot be empty",
-                        name = server.name
-                    )
-                    .bright_red()
-                );
-
-                exit(1);
-            }
-            if server.region.is_empty() {
-                if MACHINE_TYPES_WITH_OPTIONAL_REGION_ID
-                    .contains(&server.machine_type)
-                {
-                    continue;
-                }
-                eprintln!(
-                    "{}",
-                    t!("Configuration file contains errors").bright_red()
-                );
-                eprintln!(
-                    "{}",
-                    t!(
-                        "Server %{name}'s region cannot be empty",
-                        name = server.name
-                    )
-                    .bright_red()
-                );
-
-                exit(1);
-            }
+            check_server(server, config.grace_period.as_ref());
         }
 
         configs.push(config);
@@ -115,6 +93,154 @@ pub fn process_config(config: Vec<String>) -> Result<Vec<Config>> {
     Ok(configs)
 }
 
+fn check_server(server: &Server, grace_period: Option<&GracePeriod>) {
+    let optional_secret_id =
+        MACHINE_TYPES_WITH_OPTIONAL_SECRET_ID.contains(&server.machine_type);
+    // Plugins validate their own options
+    let is_plugin = matches!(server.machine_type, MachineType::Plugin { .. });
+    let optional_region = MACHINE_TYPES_WITH_OPTIONAL_REGION_ID
+        .contains(&server.machine_type)
+        || is_plugin;
+    let without_secret = MACHINE_TYPES_WITHOUT_SECRET
+        .contains(&server.machine_type)
+        || server.kubeconfig.is_some()
+        || is_plugin;
+
+    if without_secret {
+        // Local targets take no credentials, and a kubeconfig carries its own
+    } else if server.accounts.is_empty() {
+        if server.secret_id.is_empty() && !optional_secret_id {
+            exit_with_config_error(&t!(
+                "Server %{name}'s secret_id cannot be empty",
+                name = server.name
+            ));
+        }
+        if server.secret_key.is_empty() {
+            exit_with_config_error(&t!(
+                "Server %{name}'s secret_key cannot be empty",
+                name = server.name
+            ));
+        }
+    } else if !optional_secret_id
+        && let Some(empty_account) =
+            server.accounts.iter().find(|a| a.secret_id.is_empty())
+    {
+        exit_with_config_error(&t!(
+            "Account %{account} of server %{name} has an empty secret_id",
+            account = empty_account.name,
+            name = server.name
+        ));
+    }
+
+    if server.region.is_empty() && server.regions.is_empty() && !optional_region
+    {
+        exit_with_config_error(&t!(
+            "Server %{name}'s region cannot be empty",
+            name = server.name
+        ));
+    }
+
+    if server.machine_id.is_empty()
+        && server.machine_ids.is_empty()
+        && server.tags.is_empty()
+    {
+        exit_with_config_error(&t!(
+            "Server %{name}'s machine_id cannot be empty",
+            name = server.name
+        ));
+    }
+
+    if !server.tags.is_empty()
+        && !MACHINE_TYPES_WITH_TAG_SELECTOR.contains(&server.machine_type)
+    {
+        exit_with_config_error(&t!(
+            "Server %{name}'s machine type does not support tag selectors",
+            name = server.name
+        ));
+    }
+
+    // Rules holding a single address only keep previous addresses in
+    // sibling rules
+    if grace_period.is_some_and(|grace_period| !grace_period.previous_rules)
+        && MACHINE_TYPES_WITH_SINGLE_ADDRESS_RULES
+            .contains(&server.machine_type)
+    {
+        exit_with_config_error(&t!(
+            "Server %{name}'s rules hold a single address, its grace period needs previous_rules = true",
+            name = server.name
+        ));
+    }
+
+    check_extra_addresses(&server.extra_addresses);
+}
+
+/// Extra addresses must be IP addresses or CIDR blocks.
+fn check_extra_addresses(addresses: &[String]) {
+    let is_valid = |address: &str| {
+        let (ip, prefix_len) = address
+            .split_once('/')
+            .map_or((address, None), |(ip, len)| (ip, Some(len)));
+        let Ok(ip) = ip.parse::<IpAddr>() else {
+            return false;
+        };
+        let max_len = if ip.is_ipv6() { 128 } else { 32 };
+        prefix_len
+            .is_none_or(|len| len.parse::<u8>().is_ok_and(|len| len <= max_len))
+    };
+    if let Some(invalid) = addresses.iter().find(|address| !is_valid(address)) {
+        exit_with_config_error(&t!(
+            "Extra address %{address} is not an IP address or CIDR block",
+            address = invalid
+        ));
+    }
+}
+
+/// The history holds the current address, so it keeps at least one.
+fn check_address_history(history: &AddressHistory) {
+    if history.size < 1 {
+        exit_with_config_error(&t!(
+            "Address history size must be at least 1, got %{size}",
+            size = history.size
+        ));
+    }
+}
+
+fn check_team(team: &TeamOptions) {
+    if team.listen.parse::<SocketAddr>().is_err() {
+        exit_with_config_error(&t!(
+            "Team listen address %{address} is not a valid socket address",
+            address = team.listen
+        ));
+    }
+    for (i, member) in team.members.iter().enumerate() {
+        if member.name.is_empty() || member.token.is_empty() {
+            exit_with_config_error(&t!(
+                "Team member %{index} needs a name and a token",
+                index = i + 1
+            ));
+        }
+        let others = &team.members[..i];
+        if others.iter().any(|other| other.name == member.name) {
+            exit_with_config_error(&t!(
+                "Team member %{name} is defined more than once",
+                name = member.name
+            ));
+        }
+        if others.iter().any(|other| other.token == member.token) {
+            exit_with_config_error(&t!(
+                "Team member %{name} shares its token with another member",
+                name = member.name
+            ));
+        }
+    }
+}
+
+fn exit_with_config_error(message: &str) -> ! {
+    eprintln!("{}", t!("Configuration file contains errors").bright_red());
+    eprintln!("{}", message.bright_red());
+    exit(1);
+}
+
 struct ConfigContentLine {
     line_number: usize,
     content: String,
@@ -5,7 +5,10 @@ use cnxt::Colorize;
 use futures::stream::{FuturesUnordered, StreamExt};
 use ipfetcher::{Protocol, fetch_ip};
 use notification::send_notification;
-use report::{ExecutionReport, ExecutionReportIpFetching, show_brief_report};
+use report::{
+    ExecutionReport, ExecutionReportIpFetching, ExecutionReportServer,
+    ExecutionReportServerStatus, show_brief_report,
+};
 use rust_i18n::t;
 use sdk::execute_server_task;
 use solo_lib::client;
@@ -17,10 +20,13 @@ use unicode_width::UnicodeWidthStr as _;
 
 use crate::{
     config::{
-        definition::{Config, Schedule},
+        definition::{
+            Config, MACHINE_TYPES_WITH_SINGLE_ADDRESS_RULES, Schedule, Server,
+        },
         reader::process_config,
     },
     ipfetcher, notification, report, sdk,
+    state::{self, Slots, State},
 };
 
 #[derive(Debug, Clone)]
@@ -193,31 +199,32 @@ fn execute_task<'a>(
             config.servers[0].protocol
         };
 
-        match fetch_ip(protocol, config.ip_provider).await {
-            Ok((ipv4, ipv6)) => {
+        match fetch_ip(
+            protocol,
+            config.ip_provider.clone(),
+            config.allow_private_ip,
+            config.no_proxy,
+            &config.uplinks,
+        )
+        .await
+        {
+            Ok(fetched) => {
+                let (ipv4, ipv6) = (fetched.ipv4, fetched.ipv6);
                 let ipfetching_result = ExecutionReportIpFetching::Success {
                     ipv4: ipv4.clone(),
                     ipv6: ipv6.clone(),
+                    additional: fetched.additional.clone(),
+                    warnings: fetched.warnings,
                 };
 
-                let mut builder = client::new_builder();
-                if config.no_proxy {
-                    builder = builder.no_proxy();
-                }
-                let client = builder.build().unwrap();
-
-                let mut server_result = Vec::new();
-                for server in config.servers {
-                    let result = execute_server_task(
-                        tx.clone(),
-                        &client,
-                        server.clone(),
-                        ipv4.clone(),
-                        ipv6.clone(),
-                    )
-                    .await;
-                    server_result.push(result);
-                }
+                let server_result = execute_servers(
+                    tx.clone(),
+                    &config,
+                    ipv4.clone(),
+                    ipv6.clone(),
+                    &fetched.additional,
+                )
+                .await;
                 let finished_timestamp = Local::now().timestamp();
                 ExecutionReport {
                     id,
@@ -242,3 +249,261 @@ fn execute_task<'a>(
         }
     })
 }
+
+/// Execute the tasks of every server of the configuration.
+///
+/// If the state is kept, the servers whose addresses did not change since
+/// the last run are skipped.
+async fn execute_servers<'a>(
+    tx: Sender<ThreadStep>,
+    config: &Config,
+    ipv4: Cow<'static, str>,
+    ipv6: Cow<'static, str>,
+    additional: &[String],
+) -> Vec<ExecutionReportServer<'a>> {
+    let send = |name: Option<&str>, msg: Cow<'static, str>| {
+        let _ = tx.try_send(ThreadStep {
+            name: name.map(|name| Cow::Owned(name.to_string())),
+            msg,
+        });
+    };
+
+    let mut builder = client::new_builder();
+    if config.no_proxy {
+        builder = builder.no_proxy();
+    }
+    let client = builder.build().unwrap();
+
+    let state_path = (config.state.is_some()
+        || config.grace_period.is_some()
+        || config.address_history.is_some())
+    .then(|| state::path(&config.name, config.state.as_ref()));
+    let mut state = match &state_path {
+        Some(path) => Some(State::load(path).await),
+        None => None,
+    };
+    let now = Local::now().timestamp();
+
+    let current = [ipv4.as_ref(), ipv6.as_ref()];
+    let mut previous = Vec::new();
+    let mut slots = Slots::default();
+    if let Some(state) = &mut state
+        && (config.grace_period.is_some() || config.address_history.is_some())
+    {
+        state.see(&current, now);
+        state.forget(
+            config
+                .address_history
+                .as_ref()
+                .map_or(0, |history| history.size),
+            config
+                .grace_period
+                .as_ref()
+                .map(|grace_period| now - i64::from(grace_period.seconds)),
+        );
+        previous = state.previous(&current);
+        if let Some(history) = &config.address_history
+            && history.sibling_rules
+        {
+            slots =
+                state.assign_slots(&previous, history.size.saturating_sub(1));
+        }
+    }
+
+    let tasks =
+        server_tasks(config, &ipv4, &ipv6, additional, &previous, &slots);
+    let mut server_result = Vec::new();
+    for (server, ipv4, ipv6) in tasks {
+        if let (Some(state), Some(options)) = (&mut state, &config.state)
+            && state.skip(&server, &ipv4, &ipv6, options, now)
+        {
+            send(
+                Some(&server.name),
+                t!("Addresses unchanged since the last run, skipped"),
+            );
+            server_result.push(ExecutionReportServer {
+                name: Cow::Owned(server.name),
+                status: ExecutionReportServerStatus::Success {
+                    is_ip_changed: false,
+                    details: Vec::new(),
+                },
+            });
+            continue;
+        }
+
+        let result = execute_server_task(
+            tx.clone(),
+            &client,
+            server.clone(),
+            ipv4.clone(),
+            ipv6.clone(),
+        )
+        .await;
+        if let Some(state) = &mut state {
+            let is_applied = result.iter().all(|report| {
+                matches!(
+                    report.status,
+                    ExecutionReportServerStatus::Success { .. }
+                )
+            });
+            state.record(&server, &ipv4, &ipv6, now, is_applied);
+        }
+        server_result.extend(result);
+    }
+
+    if let (Some(state), Some(path)) = (state, state_path)
+        && let Err(e) = state.save(&path).await
+    {
+        send(
+            None,
+            t!("Unable to save the state file: %{error}", error = e),
+        );
+    }
+    server_result
+}
+
+/// The servers to execute along with their addresses, each server is
+/// followed by its siblings.
+///
+/// Rules holding a single address get a sibling `<rule>-alt-N` for each
+/// further fetched address, a sibling `<rule>-extra-N` for each extra
+/// address, and siblings for the previous addresses if kept.
+fn server_tasks(
+    config: &Config,
+    ipv4: &str,
+    ipv6: &str,
+    additional: &[String],
+    previous: &[String],
+    slots: &Slots,
+) -> Vec<(Server, Cow<'static, str>, Cow<'static, str>)> {
+    let previous_rules = config
+        .grace_period
+        .as_ref()
+        .is_some_and(|grace_period| grace_period.previous_rules);
+    // The address of a family from the list, or the current one
+    let pick = |address: Option<&String>, current: &str| {
+        Cow::Owned(address.map_or(current, String::as_str).to_string())
+    };
+
+    let mut tasks = Vec::new();
+    for server in &config.servers {
+        let extra_addresses: Vec<String> = config
+            .extra_addresses
+            .iter()
+            .chain(&server.extra_addresses)
+            .cloned()
+            .collect();
+        let mut server = server.clone();
+        // Further fetched and previous addresses are allowed like extra ones
+        server.extra_addresses.extend(
+            config
+                .extra_addresses
+                .iter()
+                .chain(additional)
+                .chain(previous)
+                .cloned(),
+        );
+
+        let mut siblings = Vec::new();
+        if MACHINE_TYPES_WITH_SINGLE_ADDRESS_RULES
+            .contains(&server.machine_type)
+        {
+            // The latest previous address, until the grace period is over
+            if previous_rules {
+                let latest = |is_ipv6: bool| {
+                    previous
+                        .iter()
+                        .find(|address| address.contains(':') == is_ipv6)
+                };
+                siblings.push((
+                    sibling(&server, "-prev"),
+                    pick(latest(false), ipv4),
+                    pick(latest(true), ipv6),
+                ));
+            }
+            // Further fetched addresses, such as those of the other
+            // hostnames or uplinks. The expected number of siblings is
+            // always updated.
+            let count = (config.ip_provider.expected_addresses()
+                * config.uplinks.len().max(1))
+            .saturating_sub(1);
+            siblings.extend(numbered_siblings(
+                &server, "-alt-", additional, count, ipv4, ipv6,
+            ));
+            siblings.extend(numbered_siblings(
+                &server,
+                "-extra-",
+                &extra_addresses,
+                0,
+                ipv4,
+                ipv6,
+            ));
+            for index in 0..slots.ipv4.len().max(slots.ipv6.len()) {
+                let ipv4_slot = slots.ipv4.get(index);
+                let ipv6_slot = slots.ipv6.get(index);
+                siblings.push((
+                    sibling(&server, &format!("-{}", index + 1)),
+                    pick(ipv4_slot.filter(|slot| !slot.is_empty()), ipv4),
+                    pick(ipv6_slot.filter(|slot| !slot.is_empty()), ipv6),
+                ));
+            }
+        }
+
+        tasks.push((
+            server,
+            Cow::Owned(ipv4.to_string()),
+            Cow::Owned(ipv6.to_string()),
+        ));
+        tasks.extend(siblings);
+    }
+
+    tasks
+}
+
+/// Siblings `<rule><prefix>N` holding the addresses in order, one of each
+/// IP version per sibling, at least `count` of them. A sibling without an
+/// address of its own holds the current one.
+pub fn numbered_siblings(
+    server: &Server,
+    prefix: &str,
+    addresses: &[String],
+    count: usize,
+    ipv4: &str,
+    ipv6: &str,
+) -> Vec<(Server, Cow<'static, str>, Cow<'static, str>)> {
+    let of_version = |is_ipv6: bool| -> Vec<&String> {
+        addresses
+            .iter()
+            .filter(|address| address.contains(':') == is_ipv6)
+            .collect()
+    };
+    let (addresses_ipv4, addresses_ipv6) =
+        (of_version(false), of_version(true));
+    let pick = |address: Option<&&String>, current: &str| {
+        Cow::Owned(
+            address
+                .map_or(current, |address| address.as_str())
+                .to_string(),
+        )
+    };
+
+    (0..count.max(addresses_ipv4.len()).max(addresses_ipv6.len()))
+        .map(|index| {
+            (
+                sibling(server, &format!("{prefix}{}", index + 1)),
+                pick(addresses_ipv4.get(index), ipv4),
+                pick(addresses_ipv6.get(index), ipv6),
+            )
+        })
+        .collect()
+}
+
+/// A copy of the server whose name and rules end with the suffix.
+fn sibling(server: &Server, suffix: &str) -> Server {
+    let mut sibling = server.clone();
+    sibling.name.push_str(suffix);
+    for rule in &mut sibling.rules {
+        rule.push_str(suffix);
+    }
+    sibling
+}
@@ -0,0 +1,60 @@
+//! Addresses printed by a local command, such as `tailscale ip`.
+
+use std::{net::IpAddr, process::Stdio};
+
+use anyhow::{Result, anyhow};
+use rust_i18n::t;
+use tokio::process::Command;
+
+use super::FetchContext;
+
+/// Run the command and return the first line of its output holding an
+/// address of the requested family.
+///
+/// The command is run directly, without a shell.
+pub(super) async fn run(
+    argv: &[String],
+    context: FetchContext,
+) -> Result<String> {
+    let (program, arguments) = argv
+        .split_first()
+        .ok_or_else(|| anyhow!(t!("IP command is empty")))?;
+    let child = Command::new(program)
+        .args(arguments)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let output =
+        tokio::time::timeout(context.timeout, child.wait_with_output())
+            .await
+            .map_err(|_| {
+                anyhow!(t!(
+                    "IP command %{command} timed out",
+                    command = program
+                ))
+            })??;
+    if !output.status.success() {
+        return Err(anyhow!(t!(
+            "IP command exited with status %{status}: %{stderr}",
+            status = output.status,
+            stderr = String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    // Anything else is reported as an invalid address later
+    let first = lines.clone().next().unwrap_or_default();
+    Ok(lines
+        .find(|line| {
+            line.parse::<IpAddr>()
+                .is_ok_and(|ip| context.family.matches(ip))
+        })
+        .unwrap_or(first)
+        .to_string())
+}
@@ -0,0 +1,269 @@
+//! Public address discovery through DNS.
+//!
+//! Some resolvers answer special names with the address of the querier,
+//! such as `myip.opendns.com` on `resolver1.opendns.com`, or the `TXT`
+//! record of `o-o.myaddr.l.google.com` on `ns1.google.com`.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use anyhow::{Result, anyhow};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+
+use super::{Family, FetchContext, udp_exchange};
+
+const DNS_PORT: u16 = 53;
+
+const TYPE_A: u16 = 1;
+const TYPE_TXT: u16 = 16;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DnsIpProvider {
+    /// Name to query, for example `myip.opendns.com`.
+    pub name: String,
+    /// DNS server to query, `host` or `host:port`.
+    pub server: String,
+    #[serde(default)]
+    pub record: DnsRecord,
+}
+
+/// The record holding the address.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsRecord {
+    /// `A` or `AAAA` record, depending on the requested IP version.
+    #[default]
+    Address,
+    /// `TXT` record containing the address.
+    Txt,
+}
+
+/// Query the record over UDP and return the address it holds.
+pub(super) async fn query(
+    provider: &DnsIpProvider,
+    context: FetchContext,
+) -> Result<String> {
+    let record_type = match provider.record {
+        DnsRecord::Address => address_type(context.family),
+        DnsRecord::Txt => TYPE_TXT,
+    };
+    let answers =
+        lookup(&provider.name, &provider.server, record_type, context).await?;
+
+    // A TXT record may hold other strings next to the address
+    answers
+        .iter()
+        .find(|answer| answer.parse::<IpAddr>().is_ok())
+        .or_else(|| answers.first())
+        .cloned()
+        .ok_or_else(|| anyhow!(t!("DNS server returned no answer")))
+}
+
+/// Query the records of a name over UDP and return their values.
+pub(super) async fn lookup(
+    name: &str,
+    server: &str,
+    record_type: u16,
+    context: FetchContext,
+) -> Result<Vec<String>> {
+    let id: u16 = rand::random();
+    let request = build_query(id, name, record_type)?;
+    let response = udp_exchange(server, DNS_PORT, context, &request).await?;
+    parse_response(&response, id, record_type)
+}
+
+/// The record type holding addresses of the family.
+pub(super) const fn address_type(family: Family) -> u16 {
+    match family {
+        Family::V4 => TYPE_A,
+        Family::V6 => TYPE_AAAA,
+    }
+}
+
+fn build_query(id: u16, name: &str, record_type: u16) -> Result<Vec<u8>> {
+    let mut packet = Vec::with_capacity(18 + name.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    // Recursion desired
+    packet.extend_from_slice(&0x0100_u16.to_be_bytes());
+    // One question, no answer, authority or additional records
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.trim_end_matches('.').split('.') {
+        let len = u8::try_from(label.len())
+            .ok()
+            .filter(|len| (1..64).contains(len))
+            .ok_or_else(|| {
+                anyhow!(t!("Invalid DNS name %{domain}", domain = name))
+            })?;
+        packet.push(len);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&record_type.to_be_bytes());
+    packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(packet)
+}
+
+/// Return the answers of the requested type, addresses are formatted and
+/// TXT records yield each of their strings.
+fn parse_response(
+    packet: &[u8],
+    id: u16,
+    record_type: u16,
+) -> Result<Vec<String>> {
+    let invalid = || anyhow!(t!("Invalid DNS response"));
+    let read_u16 = |offset: usize| -> Result<u16> {
+        packet
+            .get(offset..offset + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(invalid)
+    };
+
+    if read_u16(0)? != id || read_u16(2)? & 0x8000 == 0 {
+        return Err(invalid());
+    }
+    let rcode = read_u16(2)? & 0x000f;
+    if rcode != 0 {
+        return Err(anyhow!(t!(
+            "DNS server returned error code %{code}",
+            code = rcode
+        )));
+    }
+    let question_count = read_u16(4)?;
+    let answer_count = read_u16(6)?;
+
+    let mut offset = 12;
+    for _ in 0..question_count {
+        offset = skip_name(packet, offset).ok_or_else(invalid)? + 4;
+    }
+
+    let mut answers = Vec::new();
+    for _ in 0..answer_count {
+        offset = skip_name(packet, offset).ok_or_else(invalid)?;
+        let answer_type = read_u16(offset)?;
+        let data_len = usize::from(read_u16(offset + 8)?);
+        let data = packet
+            .get(offset + 10..offset + 10 + data_len)
+            .ok_or_else(invalid)?;
+        offset += 10 + data_len;
+
+        if answer_type != record_type {
+            continue;
+        }
+        match answer_type {
+            TYPE_A => {
+                let octets: [u8; 4] = data.try_into().map_err(|_| invalid())?;
+                answers.push(Ipv4Addr::from(octets).to_string());
+            }
+            TYPE_AAAA => {
+                let octets: [u8; 16] =
+                    data.try_into().map_err(|_| invalid())?;
+                answers.push(Ipv6Addr::from(octets).to_string());
+            }
+            _ => {
+                let mut rest = data;
+                while let Some((&len, tail)) = rest.split_first() {
+                    let text =
+                        tail.get(..usize::from(len)).ok_or_else(invalid)?;
+                    answers.push(String::from_utf8_lossy(text).into_owned());
+                    rest = &tail[usize::from(len)..];
+                }
+            }
+        }
+    }
+    Ok(answers)
+}
+
+/// Return the offset right after the name, which may be compressed.
+fn skip_name(packet: &[u8], mut offset: usize) -> Option<usize> {
+    loop {
+        let len = *packet.get(offset)?;
+        match len {
+            0 => return Some(offset + 1),
+            len if len & 0xc0 == 0xc0 => return Some(offset + 2),
+            len => offset += 1 + usize::from(len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: u16 = 0x1234;
+
+    /// A response to a query of `myip.opendns.com` holding the given
+    /// answers, each naming the question through a compression pointer.
+    fn response(flags: u16, answers: &[(u16, &[u8])]) -> Vec<u8> {
+        let mut packet = ID.to_be_bytes().to_vec();
+        packet.extend_from_slice(&flags.to_be_bytes());
+        packet.extend_from_slice(&1_u16.to_be_bytes());
+        let count = u16::try_from(answers.len()).unwrap();
+        packet.extend_from_slice(&count.to_be_bytes());
+        packet.extend_from_slice(&[0, 0, 0, 0]);
+        packet.extend_from_slice(b"\x04myip\x07opendns\x03com\x00");
+        packet.extend_from_slice(&TYPE_A.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        for (record_type, data) in answers {
+            packet.extend_from_slice(&[0xc0, 0x0c]);
+            packet.extend_from_slice(&record_type.to_be_bytes());
+            packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+            // TTL of 60 seconds
+            packet.extend_from_slice(&60_u32.to_be_bytes());
+            let len = u16::try_from(data.len()).unwrap();
+            packet.extend_from_slice(&len.to_be_bytes());
+            packet.extend_from_slice(data);
+        }
+        packet
+    }
+
+    #[test]
+    fn address_answers() {
+        let packet = response(
+            0x8180,
+            &[
+                // CNAME record, skipped
+                (5, b"\x03www\xc0\x0c"),
+                (TYPE_A, &[203, 0, 113, 5]),
+                (TYPE_A, &[198, 51, 100, 7]),
+            ],
+        );
+        assert_eq!(
+            parse_response(&packet, ID, TYPE_A).unwrap(),
+            ["203.0.113.5", "198.51.100.7"]
+        );
+        assert!(parse_response(&packet, ID, TYPE_AAAA).unwrap().is_empty());
+
+        let packet = response(
+            0x8180,
+            &[(
+                TYPE_AAAA,
+                &[0x20, 0x01, 0x0d, 0xb8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+            )],
+        );
+        assert_eq!(
+            parse_response(&packet, ID, TYPE_AAAA).unwrap(),
+            ["2001:db8::1"]
+        );
+    }
+
+    #[test]
+    fn txt_answers() {
+        let packet = response(0x8180, &[(TYPE_TXT, b"\x0c198.51.100.7")]);
+        assert_eq!(
+            parse_response(&packet, ID, TYPE_TXT).unwrap(),
+            ["198.51.100.7"]
+        );
+    }
+
+    #[test]
+    fn invalid_responses() {
+        let packet = response(0x8180, &[(TYPE_A, &[203, 0, 113, 5])]);
+        assert!(parse_response(&packet, 0x4321, TYPE_A).is_err());
+        assert!(parse_response(&packet[..40], ID, TYPE_A).is_err());
+        // NXDOMAIN
+        let packet = response(0x8183, &[]);
+        assert!(parse_response(&packet, ID, TYPE_A).is_err());
+    }
+}
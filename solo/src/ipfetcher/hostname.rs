@@ -0,0 +1,102 @@
+//! Addresses published under hostnames, such as DDNS names.
+
+use anyhow::{Result, anyhow};
+use futures::future::join_all;
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use tokio::net::lookup_host;
+
+use super::{FamilyAddresses, FetchContext, dns, validate};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostnameIpProvider {
+    pub names: Vec<String>,
+    /// DNS server queried directly, `host` or `host:port`. The resolver of
+    /// the system is used if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolver: Option<String>,
+}
+
+/// Resolve every name and return all their addresses of the family.
+///
+/// A name that fails to resolve, or an address that is invalid, is skipped
+/// with a warning, so that one stale name does not block the others.
+pub(super) async fn resolve(
+    provider: &HostnameIpProvider,
+    context: FetchContext,
+) -> Result<FamilyAddresses> {
+    let results = join_all(
+        provider
+            .names
+            .iter()
+            .map(|name| lookup(name, provider.resolver.as_deref(), context)),
+    )
+    .await;
+
+    let mut fetched = FamilyAddresses::default();
+    for (name, result) in provider.names.iter().zip(results) {
+        let answers = match result {
+            Ok(answers) => answers,
+            Err(e) => {
+                fetched.warnings.push(
+                    t!(
+                        "Hostname %{hostname} failed to resolve: %{error}",
+                        hostname = name,
+                        error = e
+                    )
+                    .to_string(),
+                );
+                continue;
+            }
+        };
+        for answer in answers {
+            match validate(&answer, context) {
+                Ok(ip) if !fetched.addresses.contains(&ip) => {
+                    fetched.addresses.push(ip);
+                }
+                Ok(_) => {}
+                Err(e) => fetched.warnings.push(
+                    t!(
+                        "Hostname %{hostname} skipped: %{error}",
+                        hostname = name,
+                        error = e
+                    )
+                    .to_string(),
+                ),
+            }
+        }
+    }
+
+    if fetched.addresses.is_empty() {
+        return Err(anyhow!(t!(
+            "No hostname resolved to a valid %{version} address: %{errors}",
+            version = context.family.label(),
+            errors = fetched.warnings.join("; ")
+        )));
+    }
+    Ok(fetched)
+}
+
+async fn lookup(
+    name: &str,
+    resolver: Option<&str>,
+    context: FetchContext,
+) -> Result<Vec<String>> {
+    if let Some(server) = resolver {
+        let record_type = dns::address_type(context.family);
+        return dns::lookup(name, server, record_type, context).await;
+    }
+
+    let addresses =
+        tokio::time::timeout(context.timeout, lookup_host((name, 0)))
+            .await
+            .map_err(|_| anyhow!(t!("No response from %{host}", host = name)))?
+            .map_err(|_| {
+                anyhow!(t!("Hostname %{hostname} not found", hostname = name))
+            })?;
+    Ok(addresses
+        .map(|address| address.ip())
+        .filter(|ip| context.family.matches(*ip))
+        .map(|ip| ip.to_string())
+        .collect())
+}
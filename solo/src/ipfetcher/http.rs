@@ -0,0 +1,193 @@
+//! Addresses returned by an HTTP endpoint.
+
+use std::{collections::BTreeMap, fmt::Display, time::Duration};
+
+use anyhow::{Result, anyhow};
+use reqwest::{ClientBuilder, Method};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solo_lib::client;
+
+use super::{Family, FetchContext};
+
+/// An endpoint returning the address, either a plain URL or a table of
+/// options.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum UrlIpProvider {
+    Plain(String),
+    Options(UrlOptions),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UrlOptions {
+    /// URL used for both IP versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// URL used for IPv4, overrides `url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_v4: Option<String>,
+    /// URL used for IPv6, overrides `url`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url_v6: Option<String>,
+    /// HTTP method, defaults to `GET`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Field holding the address in a JSON response, a JSON pointer such as
+    /// `/data/ip` or a dotted path such as `data.ip`. The whole body is the
+    /// address if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Timeout in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Send the request through the proxy used for the targets.
+    ///
+    /// The IP version is then decided by the proxy, so separate URLs for
+    /// IPv4 and IPv6 are usually needed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub use_proxy: bool,
+}
+
+impl Display for UrlIpProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Plain(url) => write!(f, "{url}"),
+            Self::Options(options) => {
+                let urls: Vec<&str> =
+                    [&options.url, &options.url_v4, &options.url_v6]
+                        .into_iter()
+                        .flatten()
+                        .map(String::as_str)
+                        .collect();
+                write!(f, "{}", urls.join(" | "))
+            }
+        }
+    }
+}
+
+pub(super) async fn fetch(
+    provider: &UrlIpProvider,
+    context: FetchContext,
+) -> Result<String> {
+    match provider {
+        UrlIpProvider::Plain(url) => get(url, context).await,
+        UrlIpProvider::Options(options) => request(options, context).await,
+    }
+}
+
+/// Fetch the plain text address from the URL.
+pub(super) async fn get(url: &str, context: FetchContext) -> Result<String> {
+    let client = client_builder(context.timeout, false)
+        .local_address(context.bind_address())
+        .build()?;
+    let result = client.get(url).send().await?.text().await?;
+    Ok(result.trim().to_string())
+}
+
+async fn request(
+    options: &UrlOptions,
+    context: FetchContext,
+) -> Result<String> {
+    let url = match context.family {
+        Family::V4 => options.url_v4.as_ref(),
+        Family::V6 => options.url_v6.as_ref(),
+    };
+    let url = url.or(options.url.as_ref()).ok_or_else(|| {
+        anyhow!(t!(
+            "No URL configured for %{version}",
+            version = context.family.label()
+        ))
+    })?;
+    let method = match &options.method {
+        Some(method) => Method::from_bytes(method.to_uppercase().as_bytes())
+            .map_err(|_| {
+                anyhow!(t!("Invalid HTTP method %{name}", name = method))
+            })?,
+        None => Method::GET,
+    };
+    let timeout = options.timeout.map_or(context.timeout, Duration::from_secs);
+
+    let use_proxy = options.use_proxy && !context.no_proxy;
+    let mut builder = client_builder(timeout, use_proxy);
+    // A proxy is reached through its own address family and route
+    if !use_proxy {
+        builder = builder.local_address(context.bind_address());
+    }
+    let client = builder.build()?;
+
+    let mut request = client.request(method, url);
+    for (name, value) in &options.headers {
+        request = request.header(name, value);
+    }
+    let body = request.send().await?.error_for_status()?.text().await?;
+
+    options.field.as_ref().map_or_else(
+        || Ok(body.trim().to_string()),
+        |path| extract(&body, path),
+    )
+}
+
+/// Read the field from the JSON body.
+fn extract(body: &str, path: &str) -> Result<String> {
+    let json: Value = serde_json::from_str(body)?;
+    let mut pointer = String::new();
+    if path.starts_with('/') {
+        pointer.push_str(path);
+    } else {
+        for key in path.split('.') {
+            pointer.push('/');
+            pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+        }
+    }
+    match json.pointer(&pointer) {
+        Some(Value::String(value)) => Ok(value.trim().to_string()),
+        Some(value) if !value.is_null() => Ok(value.to_string()),
+        _ => Err(anyhow!(t!(
+            "Field %{field} not found in the response",
+            field = path
+        ))),
+    }
+}
+
+fn client_builder(timeout: Duration, use_proxy: bool) -> ClientBuilder {
+    let builder = client::new_builder().timeout(timeout);
+    if use_proxy {
+        builder
+    } else {
+        builder.no_proxy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = r#"{
+        "ip": " 203.0.113.5\n",
+        "data": { "ip": "198.51.100.7", "a/b": "192.0.2.1" },
+        "list": [{ "ip": "2001:db8::1" }],
+        "port": 443,
+        "none": null
+    }"#;
+
+    #[test]
+    fn extract_field() {
+        assert_eq!(extract(BODY, "ip").unwrap(), "203.0.113.5");
+        assert_eq!(extract(BODY, "data.ip").unwrap(), "198.51.100.7");
+        assert_eq!(extract(BODY, "/data/ip").unwrap(), "198.51.100.7");
+        assert_eq!(extract(BODY, "data.a/b").unwrap(), "192.0.2.1");
+        assert_eq!(extract(BODY, "/list/0/ip").unwrap(), "2001:db8::1");
+        assert_eq!(extract(BODY, "port").unwrap(), "443");
+    }
+
+    #[test]
+    fn extract_missing_field() {
+        assert!(extract(BODY, "none").is_err());
+        assert!(extract(BODY, "data.missing").is_err());
+        assert!(extract("203.0.113.5 is not JSON", "ip").is_err());
+    }
+}
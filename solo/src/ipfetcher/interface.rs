@@ -0,0 +1,195 @@
+//! Addresses of a local network interface.
+
+use std::net::IpAddr;
+
+use anyhow::{Result, anyhow};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+
+use super::{Family, is_public};
+
+/// Which addresses of the interface are preferred.
+#[derive(
+    Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum AddressScope {
+    /// Any global address.
+    #[default]
+    Global,
+    /// Stable addresses over temporary privacy addresses.
+    Stable,
+    /// Temporary privacy addresses over stable addresses.
+    Temporary,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct InterfaceAddress {
+    ip: IpAddr,
+    /// IPv6 privacy address, only known on Linux.
+    is_temporary: bool,
+}
+
+/// Read the preferred address of a family from the interface.
+///
+/// Public addresses are preferred over the others, which are rejected later
+/// unless private addresses are allowed.
+pub(super) fn read_address(
+    name: &str,
+    scope: AddressScope,
+    family: Family,
+) -> Result<String> {
+    let mut addresses: Vec<InterfaceAddress> = list_addresses(name)?
+        .into_iter()
+        .filter(|address| match (family, address.ip) {
+            (Family::V4, IpAddr::V4(ip)) => !ip.is_loopback(),
+            (Family::V6, IpAddr::V6(ip)) => {
+                !ip.is_loopback() && !ip.is_unicast_link_local()
+            }
+            _ => false,
+        })
+        .collect();
+    addresses.sort_by_key(|address| {
+        let is_preferred = match scope {
+            AddressScope::Global => true,
+            AddressScope::Stable => !address.is_temporary,
+            AddressScope::Temporary => address.is_temporary,
+        };
+        (!is_public(address.ip), !is_preferred)
+    });
+
+    addresses
+        .first()
+        .map(|address| address.ip.to_string())
+        .ok_or_else(|| {
+            anyhow!(t!(
+                "Interface %{interface} has no usable %{version} address",
+                interface = name,
+                version = family.label()
+            ))
+        })
+}
+
+#[cfg(unix)]
+fn list_addresses(name: &str) -> Result<Vec<InterfaceAddress>> {
+    use std::{
+        ffi::CStr,
+        io,
+        net::{Ipv4Addr, Ipv6Addr},
+        ptr,
+    };
+
+    let ipv6_flags = read_ipv6_flags(name);
+
+    let mut ifaddrs: *mut libc::ifaddrs = ptr::null_mut();
+    // SAFETY: `ifaddrs` is a valid out pointer, the list is freed below.
+    if unsafe { libc::getifaddrs(&raw mut ifaddrs) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    let mut is_found = false;
+    let mut addresses = Vec::new();
+    let mut cursor = ifaddrs;
+    while !cursor.is_null() {
+        // SAFETY: `cursor` is a non-null entry of the list returned by
+        // `getifaddrs`, which stays valid until `freeifaddrs`.
+        let entry = unsafe { &*cursor };
+        cursor = entry.ifa_next;
+
+        // SAFETY: `ifa_name` is a valid C string.
+        let entry_name = unsafe { CStr::from_ptr(entry.ifa_name) };
+        if entry_name.to_bytes() != name.as_bytes() {
+            continue;
+        }
+        is_found = true;
+        if entry.ifa_addr.is_null() {
+            continue;
+        }
+
+        // SAFETY: `ifa_addr` is non-null and its family tells the actual
+        // type of the socket address.
+        let ip = unsafe {
+            match i32::from((*entry.ifa_addr).sa_family) {
+                libc::AF_INET => {
+                    let addr = entry
+                        .ifa_addr
+                        .cast::<libc::sockaddr_in>()
+                        .read_unaligned();
+                    IpAddr::V4(Ipv4Addr::from(u32::from_be(
+                        addr.sin_addr.s_addr,
+                    )))
+                }
+                libc::AF_INET6 => {
+                    let addr = entry
+                        .ifa_addr
+                        .cast::<libc::sockaddr_in6>()
+                        .read_unaligned();
+                    IpAddr::V6(Ipv6Addr::from(addr.sin6_addr.s6_addr))
+                }
+                _ => continue,
+            }
+        };
+        let (is_temporary, is_usable) = match ip {
+            IpAddr::V6(ip) => ipv6_flags
+                .iter()
+                .find(|(address, _)| *address == ip)
+                .map_or((false, true), |(_, flags)| {
+                    (flags & IFA_F_TEMPORARY != 0, flags & IFA_F_UNUSABLE == 0)
+                }),
+            IpAddr::V4(_) => (false, true),
+        };
+        if is_usable {
+            addresses.push(InterfaceAddress { ip, is_temporary });
+        }
+    }
+    // SAFETY: `ifaddrs` was returned by `getifaddrs` and is freed once.
+    unsafe { libc::freeifaddrs(ifaddrs) };
+
+    if !is_found {
+        return Err(anyhow!(t!(
+            "Interface %{interface} not found",
+            interface = name
+        )));
+    }
+    Ok(addresses)
+}
+
+#[cfg(not(unix))]
+fn list_addresses(_name: &str) -> Result<Vec<InterfaceAddress>> {
+    Err(anyhow!(t!(
+        "Reading interface addresses is not supported on this platform"
+    )))
+}
+
+/// `IFA_F_TEMPORARY` of Linux
+#[cfg(unix)]
+const IFA_F_TEMPORARY: u32 = 0x01;
+/// `IFA_F_DADFAILED`, `IFA_F_DEPRECATED` and `IFA_F_TENTATIVE` of Linux
+#[cfg(unix)]
+const IFA_F_UNUSABLE: u32 = 0x08 | 0x20 | 0x40;
+
+/// Flags of the IPv6 addresses of the interface, read from
+/// `/proc/net/if_inet6`. Empty on other systems.
+#[cfg(unix)]
+fn read_ipv6_flags(name: &str) -> Vec<(std::net::Ipv6Addr, u32)> {
+    let Ok(content) = std::fs::read_to_string("/proc/net/if_inet6") else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| {
+            // address, index, prefix length, scope, flags and name
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [address, _, _, _, flags, entry_name] = fields.as_slice()
+            else {
+                return None;
+            };
+            if *entry_name != name {
+                return None;
+            }
+            let address = u128::from_str_radix(address, 16).ok()?;
+            let flags = u32::from_str_radix(flags, 16).ok()?;
+            Some((std::net::Ipv6Addr::from(address), flags))
+        })
+        .collect()
+}
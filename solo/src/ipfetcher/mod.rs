@@ -0,0 +1,826 @@
+use std::{
+    borrow::Cow,
+    cmp::Reverse,
+    collections::BTreeMap,
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+use futures::future::{BoxFuture, FutureExt as _, join_all};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use tokio::net::{UdpSocket, lookup_host};
+
+pub use self::http::{UrlIpProvider, UrlOptions};
+use self::{
+    dns::DnsIpProvider, hostname::HostnameIpProvider, interface::AddressScope,
+    stun::StunIpProvider,
+};
+
+mod command;
+mod dns;
+mod hostname;
+mod http;
+mod interface;
+mod stun;
+
+/// Timeout of a single provider if not configured.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy)]
+#[serde(rename_all = "lowercase")]
+/// The IP protocol version the server uses.
+pub enum Protocol {
+    #[serde(rename = "v4")]
+    V4,
+    #[serde(rename = "v6")]
+    V6,
+    #[serde(rename = "both")]
+    Both,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IpProvider {
+    #[serde(rename = "url")]
+    Url(UrlIpProvider),
+    #[serde(rename = "embed")]
+    Embed(EmbedIpProvider),
+    /// Providers tried in order until one of them succeeds.
+    #[serde(rename = "fallback")]
+    Fallback(Vec<IpProviderEntry>),
+    /// Providers queried in parallel, an address is accepted only if enough
+    /// of them agree on it.
+    #[serde(rename = "quorum")]
+    Quorum(QuorumIpProvider),
+    /// Addresses of a local network interface.
+    #[serde(rename = "interface")]
+    Interface {
+        name: String,
+        #[serde(default)]
+        scope: AddressScope,
+    },
+    /// A DNS record that resolves to the address of the querier.
+    #[serde(rename = "dns")]
+    Dns(DnsIpProvider),
+    /// The mapped address of a STUN binding request.
+    #[serde(rename = "stun")]
+    Stun(StunIpProvider),
+    /// The output of a local command, such as `tailscale ip`.
+    #[serde(rename = "command")]
+    Command { argv: Vec<String> },
+    /// Fixed addresses. CIDR blocks, such as the network of an office, are
+    /// not fetched addresses and go in `extra_addresses` instead.
+    #[serde(rename = "static")]
+    Static {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        v4: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        v6: Option<String>,
+    },
+    /// Every address the hostnames resolve to. Rules holding a single
+    /// address get a sibling `<rule>-alt-N` for each further address.
+    #[serde(rename = "hostname")]
+    Hostname(HostnameIpProvider),
+}
+
+impl IpProvider {
+    /// Number of addresses of a family the provider returns, at least. A
+    /// hostname may resolve to more than one address.
+    #[must_use]
+    pub fn expected_addresses(&self) -> usize {
+        let most = |entries: &[IpProviderEntry]| {
+            entries
+                .iter()
+                .map(|entry| entry.provider.expected_addresses())
+                .max()
+                .unwrap_or(1)
+        };
+        match self {
+            Self::Fallback(entries) => most(entries),
+            Self::Quorum(quorum) => most(&quorum.providers),
+            Self::Hostname(hostname) => hostname.names.len().max(1),
+            _ => 1,
+        }
+    }
+}
+
+impl Default for IpProvider {
+    fn default() -> Self {
+        Self::Embed(EmbedIpProvider::MyExternalIp)
+    }
+}
+
+impl Display for IpProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Url(url) => url.fmt(f),
+            Self::Embed(embed) => write!(f, "{}", embed.url()),
+            Self::Fallback(_) => write!(f, "fallback"),
+            Self::Quorum(_) => write!(f, "quorum"),
+            Self::Interface { name, .. } => write!(f, "interface {name}"),
+            Self::Dns(dns) => write!(f, "dns {}@{}", dns.name, dns.server),
+            Self::Stun(stun) => write!(f, "stun {}", stun.server),
+            Self::Command { argv } => write!(f, "command {}", argv.join(" ")),
+            Self::Static { .. } => write!(f, "static"),
+            Self::Hostname(hostname) => {
+                write!(f, "hostname {}", hostname.names.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpProviderEntry {
+    #[serde(flatten)]
+    pub provider: IpProvider,
+    /// Timeout in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuorumIpProvider {
+    pub providers: Vec<IpProviderEntry>,
+    /// Number of providers that must agree, defaults to a majority. If it
+    /// is half of the providers or less, two answers reaching it is an
+    /// error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quorum: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbedIpProvider {
+    #[serde(rename = "ipecho")]
+    IpEcho,
+    #[serde(rename = "curlmyip")]
+    CurlMyIp,
+    #[serde(rename = "myexternalip")]
+    MyExternalIp,
+}
+
+impl EmbedIpProvider {
+    #[must_use]
+    pub fn url(&self) -> String {
+        match self {
+            Self::IpEcho => "https://ipecho.net/ip".to_string(),
+            Self::CurlMyIp => "https://curlmyip.net".to_string(),
+            Self::MyExternalIp => "https://myexternalip.com/raw".to_string(),
+        }
+    }
+}
+
+/// The fetched addresses, an address is empty if its family was not
+/// requested.
+#[derive(Debug, Clone)]
+pub struct FetchedIp<'a> {
+    pub ipv4: Cow<'a, str>,
+    pub ipv6: Cow<'a, str>,
+    /// Further addresses of both families, returned by providers that
+    /// resolve to more than one address.
+    pub additional: Vec<String>,
+    /// Failed providers that were skipped and disagreements between
+    /// providers.
+    pub warnings: Vec<String>,
+}
+
+/// The addresses of a family, the first one is the primary address.
+#[derive(Debug, Clone, Default)]
+struct FamilyAddresses {
+    addresses: Vec<String>,
+    /// Failed providers that were skipped and disagreements between
+    /// providers.
+    warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Family {
+    V4,
+    V6,
+}
+
+impl Family {
+    /// The unspecified address of the family, bound to by default.
+    const fn unspecified(self) -> IpAddr {
+        match self {
+            Self::V4 => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            Self::V6 => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        }
+    }
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::V4 => "IPv4",
+            Self::V6 => "IPv6",
+        }
+    }
+
+    const fn matches(self, ip: IpAddr) -> bool {
+        matches!(
+            (self, ip),
+            (Self::V4, IpAddr::V4(_)) | (Self::V6, IpAddr::V6(_))
+        )
+    }
+}
+
+/// Options shared by the providers of a single fetch.
+#[derive(Debug, Clone, Copy)]
+struct FetchContext {
+    family: Family,
+    timeout: Duration,
+    allow_private: bool,
+    /// Requests never use a proxy, even if a provider asks for it.
+    no_proxy: bool,
+    /// Local address requests are sent from, so that they leave through a
+    /// given uplink.
+    local_address: Option<IpAddr>,
+}
+
+impl FetchContext {
+    /// The context of a nested provider, with its own timeout if set.
+    fn entry(self, entry: &IpProviderEntry) -> Self {
+        Self {
+            timeout: entry.timeout.map_or(self.timeout, Duration::from_secs),
+            ..self
+        }
+    }
+
+    /// The address to bind to, so that the family and the uplink are used
+    /// for the request.
+    fn bind_address(self) -> IpAddr {
+        self.local_address
+            .unwrap_or_else(|| self.family.unspecified())
+    }
+}
+
+/// Fetch the addresses of the families required by the protocol.
+///
+/// If uplinks are given, the addresses are fetched through each of them, and
+/// the addresses of the other uplinks are returned as additional addresses.
+/// An uplink is a local address or the name of an interface.
+///
+/// # Errors
+///
+/// Returns an error if no provider returns a valid address, or if the
+/// providers do not reach a quorum. Private, loopback, link-local, CGNAT and
+/// unspecified addresses are invalid unless `allow_private` is set.
+pub async fn fetch_ip<'a>(
+    protocol: Protocol,
+    provider: IpProvider,
+    allow_private: bool,
+    no_proxy: bool,
+    uplinks: &[String],
+) -> Result<FetchedIp<'a>> {
+    let fetch = |family| {
+        let context = FetchContext {
+            family,
+            timeout: DEFAULT_TIMEOUT,
+            allow_private,
+            no_proxy,
+            local_address: None,
+        };
+        fetch_uplinks(&provider, uplinks, context)
+    };
+    let (v4, v6) = match protocol {
+        Protocol::V4 => (Some(fetch(Family::V4).await?), None),
+        Protocol::V6 => (None, Some(fetch(Family::V6).await?)),
+        Protocol::Both => {
+            let (v4_result, v6_result) =
+                tokio::join!(fetch(Family::V4), fetch(Family::V6));
+            (Some(v4_result?), Some(v6_result?))
+        }
+    };
+
+    let mut fetched = FetchedIp {
+        ipv4: Cow::Borrowed(""),
+        ipv6: Cow::Borrowed(""),
+        additional: Vec::new(),
+        warnings: Vec::new(),
+    };
+    for (family, primary) in [(v4, &mut fetched.ipv4), (v6, &mut fetched.ipv6)]
+    {
+        let Some(family) = family else {
+            continue;
+        };
+        let mut addresses = family.addresses.into_iter();
+        *primary = Cow::Owned(addresses.next().unwrap_or_default());
+        fetched.additional.extend(addresses);
+        fetched.warnings.extend(family.warnings);
+    }
+    Ok(fetched)
+}
+
+/// Fetch the addresses of a family through every uplink, or through the
+/// default route if there is none.
+///
+/// An uplink that fails is skipped with a warning.
+async fn fetch_uplinks(
+    provider: &IpProvider,
+    uplinks: &[String],
+    context: FetchContext,
+) -> Result<FamilyAddresses> {
+    if uplinks.is_empty() {
+        return fetch(provider, context).await;
+    }
+
+    let mut fetched = FamilyAddresses::default();
+    let mut bound = Vec::new();
+    for uplink in uplinks {
+        match uplink_address(uplink, context.family) {
+            Ok(Some(address)) => bound.push((uplink, address)),
+            // An address of the other family
+            Ok(None) => {}
+            Err(e) => fetched.warnings.push(
+                t!(
+                    "Uplink %{name} skipped: %{error}",
+                    name = uplink,
+                    error = e
+                )
+                .to_string(),
+            ),
+        }
+    }
+
+    let results = join_all(bound.iter().map(|(_, address)| {
+        let context = FetchContext {
+            local_address: Some(*address),
+            ..context
+        };
+        fetch(provider, context)
+    }))
+    .await;
+    for ((uplink, _), result) in bound.iter().zip(results) {
+        match result {
+            Ok(mut addresses) => {
+                fetched.warnings.append(&mut addresses.warnings);
+                for address in addresses.addresses {
+                    if !fetched.addresses.contains(&address) {
+                        fetched.addresses.push(address);
+                    }
+                }
+            }
+            Err(e) => fetched.warnings.push(
+                t!("Uplink %{name} failed: %{error}", name = uplink, error = e)
+                    .to_string(),
+            ),
+        }
+    }
+
+    if fetched.addresses.is_empty() {
+        return Err(anyhow!(t!(
+            "No uplink returned a valid %{version} address: %{errors}",
+            version = context.family.label(),
+            errors = fetched.warnings.join("; ")
+        )));
+    }
+    Ok(fetched)
+}
+
+/// The local address of the uplink, `None` if the uplink is an address of
+/// the other family.
+fn uplink_address(uplink: &str, family: Family) -> Result<Option<IpAddr>> {
+    if let Ok(ip) = uplink.parse::<IpAddr>() {
+        return Ok(family.matches(ip).then_some(ip));
+    }
+    let address =
+        interface::read_address(uplink, AddressScope::Global, family)?;
+    Ok(Some(address.parse()?))
+}
+
+/// Fetch the addresses of a family, along with the warnings raised on the
+/// way.
+fn fetch(
+    provider: &IpProvider,
+    context: FetchContext,
+) -> BoxFuture<'_, Result<FamilyAddresses>> {
+    async move {
+        let response = match provider {
+            IpProvider::Url(url) => http::fetch(url, context).await?,
+            IpProvider::Embed(embed) => {
+                http::get(&embed.url(), context).await?
+            }
+            IpProvider::Fallback(entries) => {
+                return fetch_fallback(entries, context).await;
+            }
+            IpProvider::Quorum(quorum) => {
+                return fetch_quorum(quorum, context).await;
+            }
+            IpProvider::Interface { name, scope } => {
+                interface::read_address(name, *scope, context.family)?
+            }
+            IpProvider::Dns(dns) => dns::query(dns, context).await?,
+            IpProvider::Stun(stun) => stun::binding(stun, context).await?,
+            IpProvider::Command { argv } => command::run(argv, context).await?,
+            IpProvider::Static { v4, v6 } => {
+                let ip = match context.family {
+                    Family::V4 => v4,
+                    Family::V6 => v6,
+                };
+                ip.clone().ok_or_else(|| {
+                    anyhow!(t!(
+                        "No static %{version} address configured",
+                        version = context.family.label()
+                    ))
+                })?
+            }
+            IpProvider::Hostname(hostname) => {
+                return hostname::resolve(hostname, context).await;
+            }
+        };
+        Ok(FamilyAddresses {
+            addresses: vec![validate(&response, context)?],
+            warnings: Vec::new(),
+        })
+    }
+    .boxed()
+}
+
+async fn fetch_fallback(
+    entries: &[IpProviderEntry],
+    context: FetchContext,
+) -> Result<FamilyAddresses> {
+    let mut warnings = Vec::new();
+    for entry in entries {
+        match fetch(&entry.provider, context.entry(entry)).await {
+            Ok(mut fetched) => {
+                warnings.append(&mut fetched.warnings);
+                fetched.warnings = warnings;
+                return Ok(fetched);
+            }
+            Err(e) => warnings.push(
+                t!(
+                    "IP provider %{provider} failed: %{error}",
+                    provider = entry.provider,
+                    error = e
+                )
+                .to_string(),
+            ),
+        }
+    }
+    Err(anyhow!(t!(
+        "All IP providers failed: %{errors}",
+        errors = warnings.join("; ")
+    )))
+}
+
+async fn fetch_quorum(
+    quorum: &QuorumIpProvider,
+    context: FetchContext,
+) -> Result<FamilyAddresses> {
+    let required = quorum
+        .quorum
+        .unwrap_or(quorum.providers.len() / 2 + 1)
+        .max(1);
+    let results = join_all(
+        quorum
+            .providers
+            .iter()
+            .map(|entry| fetch(&entry.provider, context.entry(entry))),
+    )
+    .await;
+
+    let mut warnings = Vec::new();
+    // Providers agree if they return the same addresses
+    let mut votes: BTreeMap<Vec<String>, usize> = BTreeMap::new();
+    for (entry, result) in quorum.providers.iter().zip(results) {
+        match result {
+            Ok(fetched) => {
+                warnings.extend(fetched.warnings);
+                *votes.entry(fetched.addresses).or_default() += 1;
+            }
+            Err(e) => warnings.push(
+                t!(
+                    "IP provider %{provider} failed: %{error}",
+                    provider = entry.provider,
+                    error = e
+                )
+                .to_string(),
+            ),
+        }
+    }
+
+    let tally = votes
+        .iter()
+        .map(|(addresses, count)| format!("{} ({count})", addresses.join(" ")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if votes.len() > 1 {
+        warnings.push(
+            t!("IP providers disagree: %{answers}", answers = tally)
+                .to_string(),
+        );
+    }
+    let mut ranked: Vec<(Vec<String>, usize)> = votes.into_iter().collect();
+    ranked.sort_by_key(|(_, count)| Reverse(*count));
+    // A quorum of half the providers or less can be reached by two answers
+    let is_tied = ranked
+        .get(1)
+        .is_some_and(|(_, count)| *count == ranked[0].1);
+    match ranked.into_iter().next() {
+        Some((addresses, count)) if count >= required && !is_tied => {
+            Ok(FamilyAddresses {
+                addresses,
+                warnings,
+            })
+        }
+        Some((_, count)) if count >= required => Err(anyhow!(t!(
+            "IP providers are tied between answers: %{answers}",
+            answers = tally
+        ))),
+        _ => Err(anyhow!(t!(
+            "IP providers did not reach a quorum of %{size}: %{answers}",
+            size = required,
+            answers = tally
+        ))),
+    }
+}
+
+/// Parse the address and check that it is a public address of the
+/// requested family.
+fn validate(response: &str, context: FetchContext) -> Result<String> {
+    let ip = match context.family {
+        Family::V4 => response.parse::<Ipv4Addr>().map(IpAddr::V4),
+        Family::V6 => response.parse::<Ipv6Addr>().map(IpAddr::V6),
+    }
+    .map_err(|_| {
+        // Error pages can be long, only the beginning is shown
+        let mut shown = response.to_string();
+        if shown.len() > 64 {
+            let end = shown.floor_char_boundary(64);
+            shown.truncate(end);
+            shown.push('…');
+        }
+        anyhow!(t!(
+            "IP provider returned an invalid address: %{content}",
+            content = shown
+        ))
+    })?;
+    if !context.allow_private && !is_public(ip) {
+        return Err(anyhow!(t!(
+            "IP provider returned a non-public address %{address}, set \
+             allow_private_ip to accept it",
+            address = ip
+        )));
+    }
+    Ok(ip.to_string())
+}
+
+/// Private, loopback, link-local, CGNAT, unspecified, broadcast and
+/// multicast addresses are not public. IPv4-mapped IPv6 addresses follow
+/// the rules of the IPv4 address.
+pub(crate) const fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_v4(ip);
+            }
+            !(ip.is_unique_local()
+                || ip.is_loopback()
+                || ip.is_unicast_link_local()
+                || ip.is_unspecified()
+                || ip.is_multicast())
+        }
+    }
+}
+
+const fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    let is_cgnat = first == 100 && (second & 0xc0) == 64;
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || is_cgnat)
+}
+
+/// Send a single datagram to the server and wait for the reply.
+///
+/// The server is `host`, `host:port` or `[ipv6]:port`, and is resolved to
+/// an address of the requested family.
+async fn udp_exchange(
+    server: &str,
+    default_port: u16,
+    context: FetchContext,
+    request: &[u8],
+) -> Result<Vec<u8>> {
+    let address = resolve_server(server, default_port, context.family).await?;
+    let socket =
+        UdpSocket::bind(SocketAddr::new(context.bind_address(), 0)).await?;
+    socket.connect(address).await?;
+    socket.send(request).await?;
+
+    let mut buffer = vec![0; 2048];
+    let len = tokio::time::timeout(context.timeout, socket.recv(&mut buffer))
+        .await
+        .map_err(|_| {
+            anyhow!(t!("No response from %{host}", host = server))
+        })??;
+    buffer.truncate(len);
+    Ok(buffer)
+}
+
+async fn resolve_server(
+    server: &str,
+    default_port: u16,
+    family: Family,
+) -> Result<SocketAddr> {
+    let candidates: Vec<SocketAddr> =
+        if let Ok(address) = server.parse::<SocketAddr>() {
+            vec![address]
+        } else if let Ok(ip) = server.parse::<IpAddr>() {
+            vec![SocketAddr::new(ip, default_port)]
+        } else if server.contains(':') {
+            lookup_host(server).await?.collect()
+        } else {
+            lookup_host((server, default_port)).await?.collect()
+        };
+    candidates
+        .into_iter()
+        .find(|address| family.matches(address.ip()))
+        .ok_or_else(|| {
+            anyhow!(t!(
+                "%{host} has no address of the requested IP version",
+                host = server
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test() {
+        let result = fetch_ip(
+            Protocol::Both,
+            IpProvider::Embed(EmbedIpProvider::MyExternalIp),
+            false,
+            false,
+            &[],
+        )
+        .await
+        .unwrap();
+        println!("{result:#?}");
+    }
+
+    fn context(family: Family, allow_private: bool) -> FetchContext {
+        FetchContext {
+            family,
+            timeout: DEFAULT_TIMEOUT,
+            allow_private,
+            no_proxy: true,
+            local_address: None,
+        }
+    }
+
+    fn fixed(v4: &str) -> IpProviderEntry {
+        IpProviderEntry {
+            provider: IpProvider::Static {
+                v4: Some(v4.to_string()),
+                v6: None,
+            },
+            timeout: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn quorum_accepts_majority() {
+        let quorum = QuorumIpProvider {
+            providers: vec![
+                fixed("203.0.113.5"),
+                fixed("198.51.100.7"),
+                fixed("203.0.113.5"),
+            ],
+            quorum: None,
+        };
+        let fetched = fetch_quorum(&quorum, context(Family::V4, false))
+            .await
+            .unwrap();
+        assert_eq!(fetched.addresses, ["203.0.113.5"]);
+        // The disagreement is reported
+        assert_eq!(fetched.warnings.len(), 1);
+
+        // Providers without an IPv6 address fail and do not vote
+        let failed = fetch_quorum(&quorum, context(Family::V6, false)).await;
+        assert!(failed.is_err());
+    }
+
+    #[tokio::test]
+    async fn quorum_rejects_minority() {
+        let quorum = QuorumIpProvider {
+            providers: vec![
+                fixed("203.0.113.5"),
+                fixed("198.51.100.7"),
+                fixed("192.0.2.1"),
+            ],
+            quorum: Some(2),
+        };
+        assert!(
+            fetch_quorum(&quorum, context(Family::V4, true))
+                .await
+                .is_err()
+        );
+
+        let quorum = QuorumIpProvider {
+            providers: vec![fixed("203.0.113.5"), fixed("10.0.0.1")],
+            quorum: Some(1),
+        };
+        let fetched = fetch_quorum(&quorum, context(Family::V4, false))
+            .await
+            .unwrap();
+        assert_eq!(fetched.addresses, ["203.0.113.5"]);
+        assert_eq!(fetched.warnings.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn quorum_rejects_ties() {
+        let quorum = QuorumIpProvider {
+            providers: vec![
+                fixed("203.0.113.5"),
+                fixed("198.51.100.7"),
+                fixed("203.0.113.5"),
+                fixed("198.51.100.7"),
+            ],
+            quorum: Some(2),
+        };
+        assert!(
+            fetch_quorum(&quorum, context(Family::V4, false))
+                .await
+                .is_err()
+        );
+
+        let quorum = QuorumIpProvider {
+            providers: vec![fixed("203.0.113.5"), fixed("198.51.100.7")],
+            quorum: Some(1),
+        };
+        assert!(
+            fetch_quorum(&quorum, context(Family::V4, false))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn fallback_skips_failed_providers() {
+        let entries = [fixed("10.0.0.1"), fixed("203.0.113.5")];
+        let fetched = fetch_fallback(&entries, context(Family::V4, false))
+            .await
+            .unwrap();
+        assert_eq!(fetched.addresses, ["203.0.113.5"]);
+        assert_eq!(fetched.warnings.len(), 1);
+    }
+
+    #[test]
+    fn validate_requires_public_address_of_family() {
+        let v4 = context(Family::V4, false);
+        let v6 = context(Family::V6, false);
+        assert_eq!(validate("203.0.113.5", v4).unwrap(), "203.0.113.5");
+        assert_eq!(validate("2606:4700::1", v6).unwrap(), "2606:4700::1");
+        assert!(validate("2606:4700::1", v4).is_err());
+        assert!(validate("203.0.113.5", v6).is_err());
+        assert!(validate("<html>error</html>", v4).is_err());
+        assert!(validate(" 203.0.113.5", v4).is_err());
+
+        assert!(validate("192.168.1.1", v4).is_err());
+        assert_eq!(
+            validate("192.168.1.1", context(Family::V4, true)).unwrap(),
+            "192.168.1.1"
+        );
+    }
+
+    #[test]
+    fn non_public_addresses() {
+        let public = |ip: &str| is_public(ip.parse().unwrap());
+        for ip in [
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.0.1",
+            "127.0.0.1",
+            "169.254.1.1",
+            "100.64.0.1",
+            "100.127.255.255",
+            "0.0.0.0",
+            "255.255.255.255",
+            "224.0.0.1",
+            "fd00::1",
+            "::1",
+            "fe80::1",
+            "::",
+            "ff02::1",
+            "::ffff:10.0.0.1",
+            "::ffff:127.0.0.1",
+            "::ffff:100.64.0.1",
+        ] {
+            assert!(!public(ip), "{ip} is not public");
+        }
+        for ip in ["1.1.1.1", "100.63.255.255", "100.128.0.1", "2606:4700::1"] {
+            assert!(public(ip), "{ip} is public");
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Public address discovery through a STUN binding request (RFC 5389).
+
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use anyhow::{Result, anyhow};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+
+use super::{FetchContext, udp_exchange};
+
+const STUN_PORT: u16 = 3478;
+
+const MAGIC_COOKIE: u32 = 0x2112_a442;
+const BINDING_REQUEST: u16 = 0x0001;
+const BINDING_SUCCESS: u16 = 0x0101;
+const MAPPED_ADDRESS: u16 = 0x0001;
+const XOR_MAPPED_ADDRESS: u16 = 0x0020;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StunIpProvider {
+    /// STUN server, `host` or `host:port`, for example
+    /// `stun.l.google.com:19302`.
+    pub server: String,
+}
+
+/// Send a binding request and return the mapped address.
+pub(super) async fn binding(
+    provider: &StunIpProvider,
+    context: FetchContext,
+) -> Result<String> {
+    let transaction_id: [u8; 12] = rand::random();
+    let mut request = Vec::with_capacity(20);
+    request.extend_from_slice(&BINDING_REQUEST.to_be_bytes());
+    // No attributes
+    request.extend_from_slice(&0_u16.to_be_bytes());
+    request.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+    request.extend_from_slice(&transaction_id);
+
+    let response =
+        udp_exchange(&provider.server, STUN_PORT, context, &request).await?;
+    parse_response(&response, &transaction_id)
+}
+
+fn parse_response(packet: &[u8], transaction_id: &[u8; 12]) -> Result<String> {
+    let invalid = || anyhow!(t!("Invalid STUN response"));
+    let read_u16 = |offset: usize| -> Result<u16> {
+        packet
+            .get(offset..offset + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(invalid)
+    };
+
+    if read_u16(0)? != BINDING_SUCCESS
+        || packet.get(8..20) != Some(transaction_id.as_slice())
+    {
+        return Err(invalid());
+    }
+    let end = (20 + usize::from(read_u16(2)?)).min(packet.len());
+
+    let mut mapped = None;
+    let mut offset = 20;
+    while offset + 4 <= end {
+        let attribute_type = read_u16(offset)?;
+        let len = usize::from(read_u16(offset + 2)?);
+        let value = packet
+            .get(offset + 4..offset + 4 + len)
+            .ok_or_else(invalid)?;
+        // Attributes are padded to 4 bytes
+        offset += 4 + len.div_ceil(4) * 4;
+
+        match attribute_type {
+            XOR_MAPPED_ADDRESS => {
+                return parse_address(value, Some(transaction_id))
+                    .ok_or_else(invalid);
+            }
+            MAPPED_ADDRESS => mapped = parse_address(value, None),
+            _ => {}
+        }
+    }
+    mapped.ok_or_else(|| anyhow!(t!("STUN response has no mapped address")))
+}
+
+/// Parse an address attribute, XOR-ed with the magic cookie and the
+/// transaction ID if given.
+fn parse_address(value: &[u8], xor: Option<&[u8; 12]>) -> Option<String> {
+    let mut mask = [0; 16];
+    if let Some(transaction_id) = xor {
+        mask[..4].copy_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        mask[4..].copy_from_slice(transaction_id);
+    }
+    // Reserved byte, family and port come before the address
+    let address = value.get(4..)?;
+    match value.get(1)? {
+        0x01 => {
+            let mut octets: [u8; 4] = address.get(..4)?.try_into().ok()?;
+            for (octet, mask) in octets.iter_mut().zip(mask) {
+                *octet ^= mask;
+            }
+            Some(Ipv4Addr::from(octets).to_string())
+        }
+        0x02 => {
+            let mut octets: [u8; 16] = address.get(..16)?.try_into().ok()?;
+            for (octet, mask) in octets.iter_mut().zip(mask) {
+                *octet ^= mask;
+            }
+            Some(Ipv6Addr::from(octets).to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSACTION_ID: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+    /// A binding success response holding the given attributes.
+    fn response(attributes: &[u8]) -> Vec<u8> {
+        let mut packet = BINDING_SUCCESS.to_be_bytes().to_vec();
+        let len = u16::try_from(attributes.len()).unwrap();
+        packet.extend_from_slice(&len.to_be_bytes());
+        packet.extend_from_slice(&MAGIC_COOKIE.to_be_bytes());
+        packet.extend_from_slice(&TRANSACTION_ID);
+        packet.extend_from_slice(attributes);
+        packet
+    }
+
+    #[test]
+    fn xor_mapped_address() {
+        // 203.0.113.5:3478
+        let v4 = response(&[
+            0x00, 0x20, 0x00, 0x08, 0x00, 0x01, 0x2c, 0x84, 0xea, 0x12, 0xd5,
+            0x47,
+        ]);
+        assert_eq!(
+            parse_response(&v4, &TRANSACTION_ID).unwrap(),
+            "203.0.113.5"
+        );
+
+        // [2001:db8::1]:3478, after an unknown attribute with padding
+        let v6 = response(&[
+            0x80, 0x22, 0x00, 0x03, b's', b'o', b'l', 0x00, 0x00, 0x20, 0x00,
+            0x14, 0x00, 0x02, 0x2c, 0x84, 0x01, 0x13, 0xa9, 0xfa, 0x01, 0x02,
+            0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0d,
+        ]);
+        assert_eq!(
+            parse_response(&v6, &TRANSACTION_ID).unwrap(),
+            "2001:db8::1"
+        );
+    }
+
+    #[test]
+    fn mapped_address() {
+        // 198.51.100.7:3478, sent by servers predating RFC 5389
+        let mapped = response(&[
+            0x00, 0x01, 0x00, 0x08, 0x00, 0x01, 0x0d, 0x96, 198, 51, 100, 7,
+        ]);
+        assert_eq!(
+            parse_response(&mapped, &TRANSACTION_ID).unwrap(),
+            "198.51.100.7"
+        );
+    }
+
+    #[test]
+    fn invalid_responses() {
+        let v4 = response(&[
+            0x00, 0x20, 0x00, 0x08, 0x00, 0x01, 0x2c, 0x84, 0xea, 0x12, 0xd5,
+            0x47,
+        ]);
+        let mut other_transaction = TRANSACTION_ID;
+        other_transaction[0] = 0;
+        assert!(parse_response(&v4, &other_transaction).is_err());
+        // Truncated attribute
+        assert!(parse_response(&v4[..24], &TRANSACTION_ID).is_err());
+        assert!(parse_response(&response(&[]), &TRANSACTION_ID).is_err());
+    }
+}
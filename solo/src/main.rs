@@ -15,17 +15,19 @@ use config::CONFIG_DETECTION_PATH;
 use rust_i18n::set_locale;
 use sys_locale::get_locale;
 
-use crate::cli::{VersionAction, conf, go, version};
+use crate::cli::{VersionAction, conf, go, serve, version};
 
 mod cli;
 mod config;
 mod exec;
+mod team;
 
 pub mod consts;
 pub mod ipfetcher;
 pub mod notification;
 pub mod report;
 pub mod sdk;
+pub mod state;
 
 rust_i18n::i18n!("locales", fallback = ["en"]);
 
@@ -63,11 +65,17 @@ async fn main() {
             HelpInfo::Main => cli::show_help(),
             HelpInfo::Go => go::show_help(),
             HelpInfo::Conf => conf::show_help(),
+            HelpInfo::Serve => serve::show_help(),
+            HelpInfo::Report => cli::report::show_help(),
             HelpInfo::Version => version::show_help(),
         },
         CliAction::RunConfig(config) => {
             exec::run(config).await;
         }
+        CliAction::Serve(config) => team::serve(config).await,
+        CliAction::Report(action) => {
+            team::report(action.url, action.ip, action.token).await;
+        }
         CliAction::ManageConfig(action) => match action {
             ManageConfigAction::List => cli::conf::show_available_configs(),
             ManageConfigAction::New => {
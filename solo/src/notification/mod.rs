@@ -45,7 +45,8 @@ pub async fn send_notification<'a>(
             matches!(
                 s.status,
                 ExecutionReportServerStatus::Success {
-                    is_ip_changed: true
+                    is_ip_changed: true,
+                    ..
                 }
             )
         });
@@ -22,6 +22,12 @@ pub enum ExecutionReportIpFetching<'a> {
     Success {
         ipv4: Cow<'a, str>,
         ipv6: Cow<'a, str>,
+        /// Further addresses returned by providers that resolve to more
+        /// than one address.
+        additional: Vec<String>,
+        /// Failed providers that were skipped and disagreements between
+        /// providers.
+        warnings: Vec<String>,
     },
     Failed {
         error: Arc<Error>,
@@ -38,6 +44,9 @@ pub struct ExecutionReportServer<'a> {
 pub enum ExecutionReportServerStatus<'a> {
     Success {
         is_ip_changed: bool,
+        /// Further facts reported by the target, such as the exit status of
+        /// a reload command.
+        details: Vec<String>,
     },
     Failed {
         error: Arc<Error>,
@@ -48,19 +57,25 @@ pub enum ExecutionReportServerStatus<'a> {
 #[must_use]
 pub fn show_brief_report(report: &ExecutionReport, color: bool) -> Vec<String> {
     let mut content: Vec<String> = Vec::new();
-    if let ExecutionReportIpFetching::Failed { error } =
-        &report.ip_fetching_status
-    {
-        content.push(format!(
-            "{} | {}",
-            t!("Failed to fetch IP").bright_red_if(color),
-            explain_error(error, color).join("\n")
-        ));
+    match &report.ip_fetching_status {
+        ExecutionReportIpFetching::Success { warnings, .. } => {
+            content.extend(show_ip_warnings(warnings, color));
+        }
+        ExecutionReportIpFetching::Failed { error } => {
+            content.push(format!(
+                "{} | {}",
+                t!("Failed to fetch IP").bright_red_if(color),
+                explain_error(error, color).join("\n")
+            ));
+        }
     }
 
     for server_status in report.server_status.clone() {
         match server_status.status {
-            ExecutionReportServerStatus::Success { is_ip_changed } => {
+            ExecutionReportServerStatus::Success {
+                is_ip_changed,
+                details,
+            } => {
                 if is_ip_changed {
                     content.push(format!(
                         "[{}] {}",
@@ -74,6 +89,7 @@ pub fn show_brief_report(report: &ExecutionReport, color: bool) -> Vec<String> {
                         t!("IP unchanged").bright_green_if(color)
                     ));
                 }
+                content.extend(show_details(&server_status.name, &details));
             }
             ExecutionReportServerStatus::Failed { error, when } => {
                 let indent_width = server_status.name.width() + 2;
@@ -124,17 +140,24 @@ pub fn show_full_report(
     ));
     if show_ipaddr {
         match &report.ip_fetching_status {
-            ExecutionReportIpFetching::Success { ipv4, ipv6 } => {
+            ExecutionReportIpFetching::Success {
+                ipv4,
+                ipv6,
+                additional,
+                warnings,
+            } => {
                 content.push(format!(
                     "{} | {}",
                     t!("IP Address").bright_green_if(color),
-                    vec![ipv4.as_ref(), ipv6.as_ref()]
+                    [ipv4.as_ref(), ipv6.as_ref()]
                         .into_iter()
+                        .chain(additional.iter().map(String::as_str))
                         .filter(|s| !s.is_empty())
                         .collect::<Vec<&str>>()
                         .join(" / ")
                         .bright_green_if(color)
                 ));
+                content.extend(show_ip_warnings(warnings, color));
             }
             ExecutionReportIpFetching::Failed { error } => {
                 content.push(format!(
@@ -148,7 +171,10 @@ pub fn show_full_report(
 
     for server_status in report.server_status.clone() {
         match server_status.status {
-            ExecutionReportServerStatus::Success { is_ip_changed } => {
+            ExecutionReportServerStatus::Success {
+                is_ip_changed,
+                details,
+            } => {
                 if is_ip_changed {
                     content.push(format!(
                         "[{}] {}",
@@ -162,6 +188,7 @@ pub fn show_full_report(
                         t!("IP unchanged").bright_green_if(color)
                     ));
                 }
+                content.extend(show_details(&server_status.name, &details));
             }
             ExecutionReportServerStatus::Failed { error, when: _ } => {
                 let indent_width = server_status.name.width() + 2;
@@ -184,6 +211,34 @@ pub fn show_full_report(
     content
 }
 
+fn show_details(name: &str, details: &[String]) -> Vec<String> {
+    let indent_width = name.width() + 2;
+    details
+        .iter()
+        .map(|detail| {
+            format!(
+                "{:indent_width$} | {}",
+                "",
+                detail,
+                indent_width = indent_width
+            )
+        })
+        .collect()
+}
+
+fn show_ip_warnings(warnings: &[String], color: bool) -> Vec<String> {
+    warnings
+        .iter()
+        .map(|warning| {
+            format!(
+                "{} | {}",
+                t!("IP fetching warning").bright_yellow_if(color),
+                warning.bright_yellow_if(color)
+            )
+        })
+        .collect()
+}
+
 fn explain_error(error: &Error, color: bool) -> Vec<String> {
     if let Some(sdkerror) = error.downcast_ref::<SdkError>() {
         explain_sdkerror(sdkerror, color)
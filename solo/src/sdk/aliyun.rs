@@ -10,6 +10,64 @@ use crate::{
     report::{ExecutionReportServer, ExecutionReportServerStatus},
 };
 
+/// Resolve an Aliyun ECS instance to its attached security groups.
+pub async fn ecs_security_groups(
+    client: &Client,
+    server: &Server,
+) -> Result<Vec<String>> {
+    use solo_lib::sdk::aliyun::ecs::{Instance, describe_instance};
+
+    let instance = Instance {
+        id: server.machine_id.clone(),
+        region: server.region.clone(),
+    };
+    let secret = Secret {
+        secret_id: server.secret_id.clone(),
+        secret_key: server.secret_key.clone(),
+    };
+    let response = describe_instance(client, &instance, &secret).await?;
+    Ok(response.response.security_group_ids.security_group_id)
+}
+
+/// Find the Aliyun ECS security groups carrying all of the given tags.
+pub async fn ecs_security_groups_by_tags(
+    client: &Client,
+    server: &Server,
+    tags: &[(String, String)],
+) -> Result<Vec<String>> {
+    use solo_lib::sdk::aliyun::ecs::list_security_groups;
+
+    let secret = Secret {
+        secret_id: server.secret_id.clone(),
+        secret_key: server.secret_key.clone(),
+    };
+    let mut security_groups = Vec::new();
+    let mut next_token = String::new();
+    loop {
+        let response = list_security_groups(
+            client,
+            &server.region,
+            &secret,
+            tags,
+            &next_token,
+        )
+        .await?;
+        let response = response.response;
+        security_groups.extend(
+            response
+                .security_groups
+                .security_group
+                .into_iter()
+                .map(|group| group.security_group_id),
+        );
+        if response.next_token.is_empty() {
+            break;
+        }
+        next_token = response.next_token;
+    }
+    Ok(security_groups)
+}
+
 /// SDK integration for Aliyun ECS.
 pub async fn ecs<'a>(
     tx: Sender<Cow<'a, str>>,
@@ -54,6 +112,7 @@ pub async fn ecs<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: true,
+                details: Vec::new(),
             },
         })
     } else {
@@ -61,6 +120,7 @@ pub async fn ecs<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: false,
+                details: Vec::new(),
             },
         })
     }
@@ -109,6 +169,306 @@ pub async fn sas<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Aliyun prefix lists.
+pub async fn prefix_list<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::aliyun::prefix_list::{
+        PrefixList, compare_rules, list_associations, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let prefix_list = PrefixList {
+        id: server.machine_id,
+        region: server.region,
+    };
+    let secret = Secret {
+        secret_id: server.secret_id,
+        secret_key: server.secret_key,
+    };
+    send(t!("Fetching prefix list"));
+    let response = list_rules(client, &prefix_list, &secret).await?;
+    let (changes, require_update) =
+        compare_rules(&response.response, &ipv4, &ipv6, &server.rules);
+    if require_update {
+        send(t!("Modifying prefix list"));
+        modify_rules(client, &prefix_list, &secret, &changes).await?;
+
+        let mut associations = 0;
+        let mut next_token = String::new();
+        loop {
+            let response =
+                list_associations(client, &prefix_list, &secret, &next_token)
+                    .await?
+                    .response;
+            associations += response
+                .prefix_list_associations
+                .prefix_list_association
+                .len();
+            if response.next_token.is_empty() {
+                break;
+            }
+            next_token = response.next_token;
+        }
+        send(t!(
+            "Prefix list is referenced by %{count} resources",
+            count = associations
+        ));
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Aliyun RDS whitelists.
+pub async fn rds<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::aliyun::rds::{
+        DbInstance, compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let instance = DbInstance {
+        id: server.machine_id,
+        region: server.region,
+    };
+    let secret = Secret {
+        secret_id: server.secret_id,
+        secret_key: server.secret_key,
+    };
+    send(t!("Fetching whitelist groups"));
+    let response = list_rules(client, &instance, &secret).await?;
+    let (groups, require_update) = compare_rules(
+        &response.response,
+        &ipv4,
+        &ipv6,
+        &server.extra_addresses,
+        &server.rules,
+    );
+    if require_update {
+        send(t!("Modifying whitelist groups"));
+        modify_rules(client, &instance, &secret, &groups).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Aliyun Redis/Tair whitelists.
+pub async fn redis<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::aliyun::redis::{
+        RedisInstance, compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let instance = RedisInstance {
+        id: server.machine_id,
+        region: server.region,
+    };
+    let secret = Secret {
+        secret_id: server.secret_id,
+        secret_key: server.secret_key,
+    };
+    send(t!("Fetching whitelist groups"));
+    let response = list_rules(client, &instance, &secret).await?;
+    let (groups, require_update) = compare_rules(
+        &response.response,
+        &ipv4,
+        &server.extra_addresses,
+        &server.rules,
+    );
+    if require_update {
+        send(t!("Modifying whitelist groups"));
+        modify_rules(client, &instance, &secret, &groups).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Aliyun Cloud Firewall address books.
+pub async fn cloudfw<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::aliyun::cloudfw::{
+        AddressBook, compare_rules, find_address_book, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let address_book = AddressBook {
+        id: server.machine_id,
+        region: server.region,
+    };
+    let secret = Secret {
+        secret_id: server.secret_id,
+        secret_key: server.secret_key,
+    };
+    send(t!("Fetching address book"));
+    let info = find_address_book(client, &address_book, &secret).await?;
+    let (info, require_update) =
+        compare_rules(&info, &ipv4, &ipv6, &server.extra_addresses);
+    if require_update {
+        send(t!("Modifying address book"));
+        modify_rules(client, &address_book, &secret, &info).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Aliyun DNS records.
+pub async fn dns<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::aliyun::dns::{
+        Domain, compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let domain = Domain {
+        name: server.machine_id,
+        region: server.region,
+    };
+    let secret = Secret {
+        secret_id: server.secret_id,
+        secret_key: server.secret_key,
+    };
+    send(t!("Fetching DNS records"));
+    let records = list_rules(client, &domain, &secret).await?;
+    let (records, require_update) =
+        compare_rules(&records, &ipv4, &ipv6, &server.rules);
+    if require_update {
+        send(t!("Modifying DNS records"));
+        modify_rules(client, &domain, &secret, &records).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
             },
         })
     } else {
@@ -116,6 +476,7 @@ pub async fn sas<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: false,
+                details: Vec::new(),
             },
         })
     }
@@ -0,0 +1,182 @@
+use std::{borrow::Cow, sync::mpsc::Sender};
+
+use anyhow::Result;
+use reqwest::Client;
+use rust_i18n::t;
+use solo_lib::sdk::cloudflare::Secret;
+
+use crate::{
+    config::definition::Server,
+    report::{ExecutionReportServer, ExecutionReportServerStatus},
+};
+
+/// SDK integration for Cloudflare IP Lists.
+///
+/// The region of the server is the account ID the list belongs to.
+pub async fn list<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::cloudflare::list::{
+        IpList, compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let list = IpList {
+        account_id: server.region,
+        id: server.machine_id,
+    };
+    let secret = Secret {
+        token: server.secret_key,
+        endpoint: server.endpoint,
+    };
+    send(t!("Fetching list items"));
+    let items = list_rules(client, &list, &secret).await?;
+    let (items, require_update) =
+        compare_rules(&items, &ipv4, &ipv6, &server.rules);
+    if require_update {
+        send(t!("Modifying list items"));
+        modify_rules(client, &list, &secret, &items).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Cloudflare IP Access Rules.
+///
+/// The machine ID of the server is the zone ID.
+pub async fn access_rule<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::cloudflare::access_rule::{
+        Zone, compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let zone = Zone {
+        id: server.machine_id,
+    };
+    let secret = Secret {
+        token: server.secret_key,
+        endpoint: server.endpoint,
+    };
+    send(t!("Fetching firewall rules"));
+    let rules = list_rules(client, &zone, &secret).await?;
+    let (rules, require_update) =
+        compare_rules(&rules, &ipv4, &ipv6, &server.rules);
+    if require_update {
+        send(t!("Modifying firewall rules"));
+        modify_rules(client, &zone, &secret, &rules).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Cloudflare DNS records.
+///
+/// The machine ID of the server is the zone ID.
+pub async fn dns<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::cloudflare::{
+        access_rule::Zone,
+        dns::{RecordOptions, compare_rules, list_rules, modify_rules},
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let zone = Zone {
+        id: server.machine_id,
+    };
+    let secret = Secret {
+        token: server.secret_key,
+        endpoint: server.endpoint,
+    };
+    let options = RecordOptions {
+        proxied: server.proxied,
+        ttl: server.ttl,
+    };
+    send(t!("Fetching DNS records"));
+    let records = list_rules(client, &zone, &secret).await?;
+    let (records, require_update) =
+        compare_rules(&records, &ipv4, &ipv6, &server.rules, &options);
+    if require_update {
+        send(t!("Modifying DNS records"));
+        modify_rules(client, &zone, &secret, &records).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
@@ -0,0 +1,67 @@
+use std::{borrow::Cow, sync::mpsc::Sender};
+
+use anyhow::Result;
+use reqwest::Client;
+use rust_i18n::t;
+use solo_lib::sdk::hetzner::Secret;
+
+use crate::{
+    config::definition::Server,
+    report::{ExecutionReportServer, ExecutionReportServerStatus},
+};
+
+/// SDK integration for Hetzner Cloud Firewalls.
+pub async fn firewall<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::hetzner::firewall::{
+        compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let firewall_id = server.machine_id;
+    let secret = Secret {
+        token: server.secret_key,
+        endpoint: server.endpoint,
+    };
+    send(t!("Fetching firewall rules"));
+    let response = list_rules(client, &firewall_id, &secret).await?;
+    let (rules, require_update) = compare_rules(
+        &response.firewall.rules,
+        &ipv4,
+        &ipv6,
+        &server.extra_addresses,
+        &server.rules,
+    );
+    if require_update {
+        send(t!("Modifying firewall rules"));
+        modify_rules(client, &firewall_id, &secret, &rules).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
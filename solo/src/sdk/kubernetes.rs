@@ -0,0 +1,176 @@
+use std::{borrow::Cow, sync::mpsc::Sender};
+
+use anyhow::{Result, anyhow};
+use reqwest::{Certificate, Client, Identity};
+use rust_i18n::t;
+use solo_lib::{
+    client,
+    sdk::kubernetes::{Resource, Secret},
+};
+
+use crate::{
+    config::definition::Server,
+    report::{ExecutionReportServer, ExecutionReportServerStatus},
+};
+
+/// SDK integration for Kubernetes network policies.
+///
+/// The machine ID of the server is `{namespace}/{name}`, and the rules are
+/// the `{protocol}:{port}` keys of the ingress rules.
+pub async fn network_policy<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::kubernetes::network_policy::{
+        compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let resource = Resource::from_id(&server.machine_id);
+    let secret = secret(&server).await?;
+    let client = &cluster_client(&secret)?;
+    send(t!("Fetching network policy"));
+    let policy = list_rules(client, &resource, &secret).await?;
+    let (policy, require_update) = compare_rules(
+        &policy,
+        &ipv4,
+        &ipv6,
+        &server.extra_addresses,
+        &server.rules,
+    );
+    if require_update {
+        send(t!("Modifying network policy"));
+        modify_rules(client, &resource, &secret, &policy).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Kubernetes Ingress allowlist annotations.
+///
+/// The machine ID of the server is `{namespace}/{name}`, and the rules are
+/// the annotation names, defaulting to the ingress-nginx
+/// `whitelist-source-range` annotation.
+pub async fn ingress<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::kubernetes::ingress::{
+        WHITELIST_SOURCE_RANGE, compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let resource = Resource::from_id(&server.machine_id);
+    let secret = secret(&server).await?;
+    let client = &cluster_client(&secret)?;
+    let annotations = if server.rules.is_empty() {
+        vec![WHITELIST_SOURCE_RANGE.to_string()]
+    } else {
+        server.rules
+    };
+    send(t!("Fetching ingress annotations"));
+    let ingress = list_rules(client, &resource, &secret).await?;
+    let (ingress, require_update) = compare_rules(
+        &ingress,
+        &ipv4,
+        &ipv6,
+        &server.extra_addresses,
+        &annotations,
+    );
+    if require_update {
+        send(t!("Modifying ingress annotations"));
+        modify_rules(client, &resource, &secret, &ingress).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// The credentials and the API server URL are read from the kubeconfig if
+/// set, or from the service account when solo runs in a pod. The
+/// `secret_key` and the `endpoint` of the server override them.
+async fn secret(server: &Server) -> Result<Secret> {
+    let mut secret = match &server.kubeconfig {
+        Some(path) => {
+            let kubeconfig = tokio::fs::read_to_string(path).await?;
+            let context =
+                (!server.region.is_empty()).then_some(server.region.as_str());
+            Secret::from_kubeconfig(&kubeconfig, context)?
+        }
+        None if std::env::var_os("KUBERNETES_SERVICE_HOST").is_some() => {
+            Secret::in_cluster()?
+        }
+        None => Secret::default(),
+    };
+    if !server.secret_key.is_empty() {
+        secret.token.clone_from(&server.secret_key);
+    }
+    if let Some(endpoint) = &server.endpoint {
+        secret.endpoint.clone_from(endpoint);
+    }
+    if secret.endpoint.is_empty() {
+        return Err(anyhow!(t!("The Kubernetes API server URL is not set")));
+    }
+    Ok(secret)
+}
+
+/// API servers usually present a certificate of a private authority, each
+/// target gets a client trusting the authority of its cluster.
+fn cluster_client(secret: &Secret) -> Result<Client> {
+    let mut builder = client::new_builder()
+        .danger_accept_invalid_certs(secret.insecure_skip_tls_verify);
+    if let Some(authority) = &secret.certificate_authority {
+        for certificate in Certificate::from_pem_bundle(authority.as_bytes())? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    if let Some(identity) = &secret.client_identity {
+        builder = builder.identity(Identity::from_pem(identity.as_bytes())?);
+    }
+    Ok(builder.build()?)
+}
@@ -0,0 +1,176 @@
+use std::{borrow::Cow, path::Path, sync::mpsc::Sender};
+
+use anyhow::{Result, anyhow};
+use rust_i18n::t;
+use tokio::{fs, process::Command};
+
+use crate::{
+    config::definition::Server,
+    report::{ExecutionReportServer, ExecutionReportServerStatus},
+};
+
+/// Placeholder replaced by each address
+const PLACEHOLDER: &str = "{ip}";
+
+/// Local allowlist file, such as an nginx `allow` list or `hosts.allow`.
+///
+/// The previous addresses are kept in a `.history` file next to the target,
+/// the extra addresses of the server are always listed after them. A failed
+/// reload leaves a `.reload-failed` marker, so that it is retried next run.
+pub async fn file<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let options = server.file.unwrap_or_default();
+    let path = server.machine_id;
+    let history_path = format!("{path}.history");
+
+    send(t!("Rendering allowlist file"));
+    let current: Vec<&str> = [ipv4.as_ref(), ipv6.as_ref()]
+        .into_iter()
+        .filter(|ip| !ip.is_empty())
+        .collect();
+    let history = read_or_empty(&history_path).await?;
+    let mut addresses = current.clone();
+    addresses.extend(
+        history
+            .lines()
+            .map(str::trim)
+            .filter(|ip| !ip.is_empty() && !current.contains(ip))
+            .take(options.keep_previous),
+    );
+    let mut listed = addresses.clone();
+    listed.extend(
+        server
+            .extra_addresses
+            .iter()
+            .map(String::as_str)
+            .filter(|address| !addresses.contains(address)),
+    );
+    let template = options.template.as_deref().unwrap_or(PLACEHOLDER);
+    let content = render(template, &listed);
+
+    let is_changed = read_or_empty(&path).await? != content;
+    if is_changed {
+        send(t!("Writing allowlist file"));
+        write_atomically(&path, &content).await?;
+    }
+
+    let new_history = addresses.iter().fold(String::new(), |mut acc, ip| {
+        acc.push_str(ip);
+        acc.push('\n');
+        acc
+    });
+    if new_history != history {
+        write_atomically(&history_path, &new_history).await?;
+    }
+
+    // A reload that failed in an earlier run is retried even though the
+    // file did not change since
+    let marker_path = format!("{path}.reload-failed");
+    let is_reload_pending = fs::try_exists(&marker_path).await?;
+    if !is_changed && !is_reload_pending {
+        return Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        });
+    }
+
+    let mut details = Vec::new();
+    if let Some(command) = options.reload_command {
+        send(t!("Running reload command"));
+        let exit_status = shell(&command).status().await;
+        let message = match &exit_status {
+            Ok(exit_status) => t!(
+                "Reload command exited with status %{status}",
+                status = exit_status
+                    .code()
+                    .map_or_else(|| exit_status.to_string(), |c| c.to_string())
+            ),
+            Err(e) => t!("Unable to run reload command: %{error}", error = e),
+        };
+        if !exit_status.is_ok_and(|exit_status| exit_status.success()) {
+            write_atomically(&marker_path, &message).await?;
+            return Err(anyhow!(message));
+        }
+        details.push(message.into_owned());
+    }
+    if is_reload_pending {
+        fs::remove_file(&marker_path).await?;
+    }
+
+    Ok(ExecutionReportServer {
+        name: server_name,
+        status: ExecutionReportServerStatus::Success {
+            is_ip_changed: true,
+            details,
+        },
+    })
+}
+
+/// Lines containing `{ip}` are repeated for every address, the others are
+/// kept as is.
+fn render(template: &str, addresses: &[&str]) -> String {
+    let mut content = String::new();
+    for line in template.lines() {
+        if line.contains(PLACEHOLDER) {
+            for ip in addresses {
+                content.push_str(&line.replace(PLACEHOLDER, ip));
+                content.push('\n');
+            }
+        } else {
+            content.push_str(line);
+            content.push('\n');
+        }
+    }
+    content
+}
+
+async fn read_or_empty(path: &str) -> Result<String> {
+    match fs::read_to_string(path).await {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write to a temporary file first and rename it, so that readers never see
+/// a partially written file.
+async fn write_atomically(path: &str, content: &str) -> Result<()> {
+    let tmp_path = format!("{path}.tmp");
+    if let Some(parent) = Path::new(path).parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(&tmp_path, content).await?;
+    fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+#[cfg(windows)]
+pub(super) fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+#[cfg(not(windows))]
+pub(super) fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
@@ -5,6 +5,7 @@ use std::{
 };
 
 use reqwest::Client;
+use target::resolve_targets;
 use tokio::sync::mpsc::Sender;
 
 use super::report::{ExecutionReportServer, ExecutionReportServerStatus};
@@ -14,16 +15,60 @@ use crate::{
 };
 
 mod aliyun;
+mod cloudflare;
+mod digitalocean;
+mod hetzner;
+mod kubernetes;
+mod local;
+mod plugin;
 mod qcloud;
 mod rainyun;
+mod target;
+mod vultr;
 
 /// Execute a task for a single server.
+///
+/// A server may expand into several targets, for example a list of machine
+/// IDs or an instance ID that is attached to more than one security group.
+/// Each target is reported separately.
 pub async fn execute_server_task<'a>(
     tx: Sender<ThreadStep>,
 
     client: &Client,
     server: Server,
 
+    ipv4: Cow<'static, str>,
+    ipv6: Cow<'static, str>,
+) -> Vec<ExecutionReportServer<'a>> {
+    let targets = resolve_targets(&tx, client, &server).await;
+
+    let mut reports = Vec::with_capacity(targets.len());
+    for target in targets {
+        let report = match target {
+            Ok(target) => {
+                execute_target_task(
+                    tx.clone(),
+                    client,
+                    target,
+                    ipv4.clone(),
+                    ipv6.clone(),
+                )
+                .await
+            }
+            Err(report) => report,
+        };
+        reports.push(report);
+    }
+    reports
+}
+
+/// Execute a task for a single target.
+async fn execute_target_task<'a>(
+    tx: Sender<ThreadStep>,
+
+    client: &Client,
+    server: Server,
+
     ipv4: Cow<'static, str>,
     ipv6: Cow<'static, str>,
 ) -> ExecutionReportServer<'a> {
@@ -47,19 +92,73 @@ pub async fn execute_server_task<'a>(
     });
 
     let result = match server.machine_type {
-        MachineType::QcloudCvm => {
+        // Qcloud database instances are resolved to their security groups
+        MachineType::QcloudCvm | MachineType::QcloudCdb => {
             qcloud::cvm(txx, client, server, ipv4, ipv6).await
         }
         MachineType::QcloudLighthouse => {
             qcloud::lighthouse(txx, client, server, ipv4).await
         }
+        MachineType::QcloudAddressTemplate => {
+            qcloud::address_template(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::QcloudDnspod => {
+            qcloud::dnspod(txx, client, server, ipv4, ipv6).await
+        }
         MachineType::AliyunEcs => {
             aliyun::ecs(txx, client, server, ipv4, ipv6).await
         }
         MachineType::AliyunSas => aliyun::sas(txx, client, server, ipv4).await,
+        MachineType::AliyunPrefixList => {
+            aliyun::prefix_list(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::AliyunRds => {
+            aliyun::rds(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::AliyunRedis => {
+            aliyun::redis(txx, client, server, ipv4).await
+        }
+        MachineType::AliyunCloudfw => {
+            aliyun::cloudfw(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::AliyunDns => {
+            aliyun::dns(txx, client, server, ipv4, ipv6).await
+        }
         MachineType::RainyunRcs => {
             rainyun::rcs(txx, client, server, ipv4).await
         }
+        MachineType::CloudflareList => {
+            cloudflare::list(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::CloudflareAccessRule => {
+            cloudflare::access_rule(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::CloudflareDns => {
+            cloudflare::dns(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::DigitaloceanFirewall => {
+            digitalocean::firewall(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::HetznerFirewall => {
+            hetzner::firewall(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::VultrFirewall => {
+            vultr::firewall(txx, client, server, ipv4, ipv6).await
+        }
+        MachineType::KubernetesNetworkPolicy => {
+            kubernetes::network_policy(txx, server, ipv4, ipv6).await
+        }
+        MachineType::KubernetesIngress => {
+            kubernetes::ingress(txx, server, ipv4, ipv6).await
+        }
+        MachineType::File => local::file(txx, server, ipv4, ipv6).await,
+        MachineType::Plugin {
+            ref command,
+            timeout,
+        } => {
+            let command = command.clone();
+            plugin::plugin(txx, &command, timeout, server, ipv4, ipv6).await
+        }
     };
     match result {
         Ok(r) => r,
@@ -0,0 +1,251 @@
+//! External plugins for targets that are not supported by solo.
+//!
+//! A plugin is an executable that is spawned once per step. Solo writes a
+//! single JSON request to its stdin and reads a single JSON response from
+//! its stdout.
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "action": "list",
+//!     "target": {
+//!         "name": "appliance",
+//!         "machine_id": "edge-1",
+//!         "region": "",
+//!         "secret_id": "",
+//!         "secret_key": "",
+//!         "endpoint": null
+//!     },
+//!     "ipv4": "1.2.3.4",
+//!     "ipv6": "",
+//!     "extra_addresses": ["203.0.113.0/24"],
+//!     "matched_rules": ["ssh"],
+//!     "rules": []
+//! }
+//! ```
+//!
+//! - `list` asks for the current rules, `rules` is empty.
+//! - `compare` carries the listed rules, the plugin returns the rules to be
+//!   modified and whether an update is required.
+//! - `apply` carries the rules returned by `compare`.
+//!
+//! `extra_addresses` are IP addresses or CIDR blocks that should be allowed
+//! next to the current addresses.
+//!
+//! Rules are `{"name": "ssh", "addresses": ["1.2.3.4/32"], "data": …}`,
+//! where `data` is opaque to solo and passed back as is. A response looks
+//! like `{"rules": [], "require_update": false}`, and failures are reported
+//! as `{"error": {"code": "…", "message": "…"}}` or with a non-zero exit
+//! status. A plugin still running after the timeout is killed.
+
+use std::{borrow::Cow, process::Stdio, sync::mpsc::Sender, time::Duration};
+
+use anyhow::{Result, anyhow};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solo_lib::SdkError;
+use tokio::io::AsyncWriteExt;
+
+use super::local::shell;
+use crate::{
+    config::definition::Server,
+    report::{ExecutionReportServer, ExecutionReportServerStatus},
+};
+
+/// Version of the plugin protocol
+const PROTOCOL_VERSION: u32 = 1;
+
+/// Time a single call of a plugin may take, unless configured otherwise.
+const DEFAULT_TIMEOUT: Duration = Duration::from_mins(1);
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Action {
+    List,
+    Compare,
+    Apply,
+}
+
+#[derive(Debug, Serialize)]
+struct PluginRequest<'a> {
+    version: u32,
+    action: Action,
+    target: PluginTarget<'a>,
+    ipv4: &'a str,
+    ipv6: &'a str,
+    extra_addresses: &'a [String],
+    matched_rules: &'a [String],
+    rules: &'a [PluginRule],
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+struct PluginTarget<'a> {
+    name: &'a str,
+    machine_id: &'a str,
+    region: &'a str,
+    secret_id: &'a str,
+    secret_key: &'a str,
+    endpoint: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginRule {
+    pub name: String,
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    /// Plugin specific data, passed back as is
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub data: Value,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PluginResponse {
+    #[serde(default)]
+    rules: Vec<PluginRule>,
+    #[serde(default)]
+    require_update: bool,
+    #[serde(default)]
+    error: Option<PluginError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PluginError {
+    #[serde(default)]
+    code: String,
+    message: String,
+}
+
+/// Integration for external plugins.
+pub async fn plugin<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    command: &str,
+    timeout: Option<u64>,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let target = PluginTarget {
+        name: &server.name,
+        machine_id: &server.machine_id,
+        region: &server.region,
+        secret_id: &server.secret_id,
+        secret_key: &server.secret_key,
+        endpoint: server.endpoint.as_deref(),
+    };
+    let timeout = timeout.map_or(DEFAULT_TIMEOUT, Duration::from_secs);
+    let request = |action, rules| PluginRequest {
+        version: PROTOCOL_VERSION,
+        action,
+        target,
+        ipv4: &ipv4,
+        ipv6: &ipv6,
+        extra_addresses: &server.extra_addresses,
+        matched_rules: &server.rules,
+        rules,
+    };
+
+    send(t!("Fetching plugin rules"));
+    let listed = call(command, timeout, &request(Action::List, &[])).await?;
+    let compared =
+        call(command, timeout, &request(Action::Compare, &listed.rules))
+            .await?;
+    if compared.require_update {
+        send(t!("Applying plugin rules"));
+        call(command, timeout, &request(Action::Apply, &compared.rules))
+            .await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// Spawn the plugin, send the request and wait for the response.
+async fn call(
+    command: &str,
+    timeout: Duration,
+    request: &PluginRequest<'_>,
+) -> Result<PluginResponse> {
+    let mut command = shell(command);
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // Processes spawned by the plugin join its group, which is killed as a
+    // whole on timeout
+    #[cfg(unix)]
+    command.process_group(0);
+    let mut child = command.spawn()?;
+    let pid = child.id();
+    let mut payload = serde_json::to_vec(request)?;
+    payload.push(b'\n');
+    let stdin = child.stdin.take();
+    // The child is killed when the call is dropped on timeout
+    let output = tokio::time::timeout(timeout, async move {
+        if let Some(mut stdin) = stdin {
+            stdin.write_all(&payload).await?;
+        }
+        child.wait_with_output().await
+    })
+    .await
+    .map_err(|_| {
+        kill_group(pid);
+        anyhow!(t!(
+            "Plugin did not respond within %{seconds} seconds",
+            seconds = timeout.as_secs()
+        ))
+    })??;
+    if !output.status.success() {
+        return Err(anyhow!(t!(
+            "Plugin exited with status %{status}: %{stderr}",
+            status = output.status,
+            stderr = String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    let response: PluginResponse = serde_json::from_slice(&output.stdout)?;
+    if let Some(error) = response.error {
+        return Err(SdkError {
+            request_id: String::new(),
+            code: error.code,
+            message: error.message,
+        }
+        .into());
+    }
+    Ok(response)
+}
+
+#[cfg(unix)]
+fn kill_group(pid: Option<u32>) {
+    if let Some(pid) = pid.and_then(|pid| i32::try_from(pid).ok()) {
+        // SAFETY: `kill` takes no pointers.
+        unsafe {
+            libc::kill(-pid, libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+const fn kill_group(_pid: Option<u32>) {}
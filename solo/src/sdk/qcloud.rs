@@ -1,6 +1,6 @@
 use std::{borrow::Cow, sync::mpsc::Sender};
 
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use reqwest::Client;
 use rust_i18n::t;
 use solo_lib::sdk::qcloud::Secret;
@@ -10,6 +10,98 @@ use crate::{
     report::{ExecutionReportServer, ExecutionReportServerStatus},
 };
 
+/// Resolve a Qcloud CVM instance to its attached security groups.
+pub async fn cvm_security_groups(
+    client: &Client,
+    server: &Server,
+) -> Result<Vec<String>> {
+    use solo_lib::sdk::qcloud::cvm::{Instance, describe_instance};
+
+    let instance = Instance {
+        id: server.machine_id.clone(),
+        region: server.region.clone(),
+    };
+    let secret = Secret {
+        secret_id: server.secret_id.clone(),
+        secret_key: server.secret_key.clone(),
+    };
+    let response = describe_instance(client, &instance, &secret).await?;
+    response
+        .response
+        .data
+        .instance_set
+        .into_iter()
+        .next()
+        .map(|instance| instance.security_group_ids)
+        .ok_or_else(|| {
+            anyhow!(t!("Instance %{id} not found", id = server.machine_id))
+        })
+}
+
+/// Resolve a Qcloud database instance to its bound security groups.
+pub async fn cdb_security_groups(
+    client: &Client,
+    server: &Server,
+) -> Result<Vec<String>> {
+    use solo_lib::sdk::qcloud::cdb::{DbInstance, describe_security_groups};
+
+    let instance = DbInstance {
+        id: server.machine_id.clone(),
+        region: server.region.clone(),
+    };
+    let secret = Secret {
+        secret_id: server.secret_id.clone(),
+        secret_key: server.secret_key.clone(),
+    };
+    let response = describe_security_groups(client, &instance, &secret).await?;
+    Ok(response
+        .response
+        .data
+        .groups
+        .into_iter()
+        .map(|group| group.security_group_id)
+        .collect())
+}
+
+/// Find the Qcloud CVM security groups carrying all of the given tags.
+pub async fn cvm_security_groups_by_tags(
+    client: &Client,
+    server: &Server,
+    tags: &[(String, String)],
+) -> Result<Vec<String>> {
+    use solo_lib::sdk::qcloud::cvm::list_security_groups;
+
+    let secret = Secret {
+        secret_id: server.secret_id.clone(),
+        secret_key: server.secret_key.clone(),
+    };
+    let mut security_groups = Vec::new();
+    loop {
+        let response = list_security_groups(
+            client,
+            &server.region,
+            &secret,
+            tags,
+            security_groups.len(),
+        )
+        .await?;
+        let data = response.response.data;
+        let page_len = data.security_group_set.len();
+        security_groups.extend(
+            data.security_group_set
+                .into_iter()
+                .map(|group| group.security_group_id),
+        );
+        if page_len == 0
+            || security_groups.len()
+                >= usize::try_from(data.total_count).unwrap_or_default()
+        {
+            break;
+        }
+    }
+    Ok(security_groups)
+}
+
 /// SDK integration for Qcloud CVM.
 pub async fn cvm<'a>(
     tx: Sender<Cow<'a, str>>,
@@ -58,6 +150,7 @@ pub async fn cvm<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: true,
+                details: Vec::new(),
             },
         })
     } else {
@@ -65,6 +158,7 @@ pub async fn cvm<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: false,
+                details: Vec::new(),
             },
         })
     }
@@ -110,6 +204,122 @@ pub async fn lighthouse<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: true,
+                details: Vec::new(),
+            },
+        })
+    } else {
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: false,
+                details: Vec::new(),
+            },
+        })
+    }
+}
+
+/// SDK integration for Qcloud IP address templates.
+pub async fn address_template<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::qcloud::template::{
+        Template, compare_rules, find_templates, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let template = Template {
+        id: server.machine_id,
+        region: server.region,
+    };
+    let secret = Secret {
+        secret_id: server.secret_id,
+        secret_key: server.secret_key,
+    };
+    send(t!("Fetching address templates"));
+    let address_templates = find_templates(client, &template, &secret).await?;
+    let mut is_ip_changed = false;
+    for address_template in address_templates {
+        let (addresses, require_update) = compare_rules(
+            &address_template,
+            &ipv4,
+            &ipv6,
+            &server.extra_addresses,
+            &server.rules,
+        );
+        if require_update {
+            send(t!("Modifying address templates"));
+            modify_rules(
+                client,
+                &template,
+                &secret,
+                &address_template.address_template_id,
+                &addresses,
+            )
+            .await?;
+            is_ip_changed = true;
+        }
+    }
+
+    Ok(ExecutionReportServer {
+        name: server_name,
+        status: ExecutionReportServerStatus::Success {
+            is_ip_changed,
+            details: Vec::new(),
+        },
+    })
+}
+
+/// SDK integration for Qcloud Dnspod records.
+pub async fn dnspod<'a>(
+    tx: Sender<Cow<'a, str>>,
+
+    client: &Client,
+    server: Server,
+
+    ipv4: Cow<'a, str>,
+    ipv6: Cow<'a, str>,
+) -> Result<ExecutionReportServer<'a>> {
+    use solo_lib::sdk::qcloud::dnspod::{
+        Domain, compare_rules, list_rules, modify_rules,
+    };
+
+    let server_name = Cow::<'a, str>::Owned(server.name.clone());
+
+    let send = move |msg: Cow<'a, str>| {
+        let _ = tx.send(msg);
+    };
+
+    let domain = Domain {
+        name: server.machine_id,
+    };
+    let secret = Secret {
+        secret_id: server.secret_id,
+        secret_key: server.secret_key,
+    };
+    send(t!("Fetching DNS records"));
+    let records = list_rules(client, &domain, &secret).await?;
+    let (records, require_update) =
+        compare_rules(&records, &ipv4, &ipv6, &server.rules);
+    if require_update {
+        send(t!("Modifying DNS records"));
+        modify_rules(client, &domain, &secret, &records).await?;
+
+        Ok(ExecutionReportServer {
+            name: server_name,
+            status: ExecutionReportServerStatus::Success {
+                is_ip_changed: true,
+                details: Vec::new(),
             },
         })
     } else {
@@ -117,6 +327,7 @@ pub async fn lighthouse<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: false,
+                details: Vec::new(),
             },
         })
     }
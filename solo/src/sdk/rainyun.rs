@@ -42,6 +42,7 @@ pub async fn rcs<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: true,
+                details: Vec::new(),
             },
         })
     } else {
@@ -49,6 +50,7 @@ pub async fn rcs<'a>(
             name: server_name,
             status: ExecutionReportServerStatus::Success {
                 is_ip_changed: false,
+                details: Vec::new(),
             },
         })
     }
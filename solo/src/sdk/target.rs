@@ -0,0 +1,232 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
+    iter,
+};
+
+use anyhow::{Error, Result, anyhow};
+use reqwest::Client;
+use rust_i18n::t;
+use tokio::sync::mpsc::Sender;
+
+use super::{aliyun, qcloud};
+use crate::{
+    config::definition::{MachineType, Server},
+    exec::ThreadStep,
+    report::{ExecutionReportServer, ExecutionReportServerStatus},
+};
+
+/// A single target of a server.
+///
+/// Targets that could not be resolved carry the report of the failure
+/// instead.
+pub type Target<'a> = Result<Server, ExecutionReportServer<'a>>;
+
+/// Expand a server into the targets it covers.
+///
+/// Every combination of account, region and machine ID is a separate
+/// target, where the machine IDs include the resources matched by the tag
+/// selector. Instance IDs are resolved to the security groups attached to
+/// them.
+pub async fn resolve_targets<'a>(
+    tx: &Sender<ThreadStep>,
+    client: &Client,
+    server: &Server,
+) -> Vec<Target<'a>> {
+    let accounts: Vec<(&str, &str, &str)> = if server.accounts.is_empty() {
+        vec![("", &server.secret_id, &server.secret_key)]
+    } else {
+        server
+            .accounts
+            .iter()
+            .map(|a| (a.name.as_str(), a.secret_id.as_str(), &*a.secret_key))
+            .collect()
+    };
+    // A region listed twice would run its targets twice
+    let mut seen = HashSet::new();
+    let mut regions: Vec<&str> = iter::once(server.region.as_str())
+        .chain(server.regions.iter().map(String::as_str))
+        .filter(|region| !region.is_empty() && seen.insert(*region))
+        .collect();
+    if regions.is_empty() {
+        regions.push("");
+    }
+    let mut seen = HashSet::new();
+    let machine_ids: Vec<&str> = iter::once(server.machine_id.as_str())
+        .chain(server.machine_ids.iter().map(String::as_str))
+        .filter(|id| !id.is_empty() && seen.insert(*id))
+        .collect();
+    let tags: Vec<(String, String)> = server
+        .tags
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    let name_with_id = machine_ids.len() > 1 || !tags.is_empty();
+
+    let mut targets = Vec::new();
+    for (account, secret_id, secret_key) in &accounts {
+        for region in &regions {
+            let mut name = server.name.clone();
+            if accounts.len() > 1 {
+                name = format!("{name}/{account}");
+            }
+            if regions.len() > 1 {
+                name = format!("{name}/{region}");
+            }
+            let base = Server {
+                name,
+                machine_id: String::new(),
+                machine_ids: Vec::new(),
+                tags: BTreeMap::new(),
+                region: (*region).to_string(),
+                regions: Vec::new(),
+                secret_id: (*secret_id).to_string(),
+                secret_key: (*secret_key).to_string(),
+                accounts: Vec::new(),
+                ..server.clone()
+            };
+
+            let mut ids: Vec<String> =
+                machine_ids.iter().map(ToString::to_string).collect();
+            if !tags.is_empty() {
+                let when = t!("Searching for tagged resources");
+                send_step(tx, &base.name, when.clone()).await;
+                match select_by_tags(client, &base, &tags).await {
+                    Ok(selected) => ids.extend(
+                        selected
+                            .into_iter()
+                            .filter(|id| !machine_ids.contains(&id.as_str())),
+                    ),
+                    Err(e) => {
+                        targets.push(Err(failed(base.name, e, when)));
+                        continue;
+                    }
+                }
+                if ids.is_empty() {
+                    targets.push(Err(failed(
+                        base.name,
+                        anyhow!(t!("No resources match the tag selector")),
+                        when,
+                    )));
+                    continue;
+                }
+            }
+
+            for id in ids {
+                let name = if name_with_id {
+                    format!("{}/{id}", base.name)
+                } else {
+                    base.name.clone()
+                };
+                let target = Server {
+                    name,
+                    machine_id: id,
+                    ..base.clone()
+                };
+                targets.extend(resolve_instance(tx, client, target).await);
+            }
+        }
+    }
+    targets
+}
+
+/// Find the resources carrying all of the given tags.
+async fn select_by_tags(
+    client: &Client,
+    server: &Server,
+    tags: &[(String, String)],
+) -> Result<Vec<String>> {
+    match server.machine_type {
+        MachineType::QcloudCvm => {
+            qcloud::cvm_security_groups_by_tags(client, server, tags).await
+        }
+        MachineType::AliyunEcs => {
+            aliyun::ecs_security_groups_by_tags(client, server, tags).await
+        }
+        // Rejected when reading the configuration
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Resolve an instance ID to the security groups attached to it, every
+/// other target is returned as is.
+///
+/// Qcloud database instances are always resolved, the resulting targets are
+/// updated as CVM security groups.
+async fn resolve_instance<'a>(
+    tx: &Sender<ThreadStep>,
+    client: &Client,
+    server: Server,
+) -> Vec<Target<'a>> {
+    use solo_lib::sdk::{aliyun::ecs, qcloud::cvm};
+
+    let when = t!("Resolving security groups");
+    let security_groups = match server.machine_type {
+        MachineType::QcloudCvm if cvm::is_instance_id(&server.machine_id) => {
+            send_step(tx, &server.name, when.clone()).await;
+            qcloud::cvm_security_groups(client, &server).await
+        }
+        MachineType::AliyunEcs if ecs::is_instance_id(&server.machine_id) => {
+            send_step(tx, &server.name, when.clone()).await;
+            aliyun::ecs_security_groups(client, &server).await
+        }
+        MachineType::QcloudCdb => {
+            send_step(tx, &server.name, when.clone()).await;
+            qcloud::cdb_security_groups(client, &server).await
+        }
+        _ => return vec![Ok(server)],
+    };
+
+    match security_groups {
+        Ok(security_groups) if security_groups.is_empty() => {
+            let error = anyhow!(t!(
+                "Instance %{id} has no security groups attached",
+                id = server.machine_id
+            ));
+            vec![Err(failed(server.name, error, when))]
+        }
+        Ok(security_groups) => security_groups
+            .into_iter()
+            .map(|id| {
+                let machine_type = match server.machine_type {
+                    MachineType::QcloudCdb => MachineType::QcloudCvm,
+                    ref machine_type => machine_type.clone(),
+                };
+                Ok(Server {
+                    name: format!("{}/{}", server.name, id),
+                    machine_id: id,
+                    machine_type,
+                    ..server.clone()
+                })
+            })
+            .collect(),
+        Err(e) => vec![Err(failed(server.name, e, when))],
+    }
+}
+
+async fn send_step(
+    tx: &Sender<ThreadStep>,
+    name: &str,
+    msg: Cow<'static, str>,
+) {
+    let _ = tx
+        .send(ThreadStep {
+            name: Some(Cow::Owned(name.to_string())),
+            msg,
+        })
+        .await;
+}
+
+fn failed(
+    name: String,
+    error: Error,
+    when: Cow<'_, str>,
+) -> ExecutionReportServer<'_> {
+    ExecutionReportServer {
+        name: Cow::Owned(name),
+        status: ExecutionReportServerStatus::Failed {
+            error: error.into(),
+            when,
+        },
+    }
+}
@@ -0,0 +1,415 @@
+//! Addresses last applied to each server, so that the servers whose
+//! addresses did not change are not contacted, the addresses seen in recent
+//! runs and the addresses reported by team members.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use tokio::fs;
+
+use crate::config::{
+    CONFIG_DETECTION_PATH,
+    definition::{Server, StateOptions},
+};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    servers: BTreeMap<String, ServerState>,
+    /// Addresses fetched in recent runs, the most recently seen first.
+    #[serde(default)]
+    addresses: Vec<SeenAddress>,
+    /// Previous addresses held by the sibling rules.
+    #[serde(default)]
+    slots: Slots,
+    /// Addresses reported by the team members, by name.
+    #[serde(default)]
+    members: BTreeMap<String, MemberState>,
+}
+
+/// Addresses reported by a team member.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemberState {
+    pub ipv4: String,
+    pub ipv6: String,
+    /// Time the IPv4 address was last reported, as a Unix timestamp.
+    #[serde(default)]
+    pub ipv4_reported_at: i64,
+    /// Time the IPv6 address was last reported, as a Unix timestamp.
+    #[serde(default)]
+    pub ipv6_reported_at: i64,
+    /// Whether the addresses were applied to every server.
+    pub is_applied: bool,
+}
+
+impl MemberState {
+    /// Forget the addresses last reported before the given time, so that a
+    /// member who moved to a network of the other IP version does not keep
+    /// its old address. Returns whether an address was forgotten.
+    pub fn expire(&mut self, before: i64) -> bool {
+        let mut expired = false;
+        for (address, reported_at) in [
+            (&mut self.ipv4, self.ipv4_reported_at),
+            (&mut self.ipv6, self.ipv6_reported_at),
+        ] {
+            if !address.is_empty() && reported_at < before {
+                address.clear();
+                expired = true;
+            }
+        }
+        if expired {
+            self.is_applied = false;
+        }
+        expired
+    }
+}
+
+/// Addresses of the sibling rules `<rule>-1`, `<rule>-2` and so on, by IP
+/// version. An empty slot holds the current address.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Slots {
+    pub ipv4: Vec<String>,
+    pub ipv6: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SeenAddress {
+    address: String,
+    /// Time of the last run that fetched the address, as a Unix timestamp.
+    last_seen: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ServerState {
+    ipv4: String,
+    ipv6: String,
+    /// Hash of the server definition, a changed definition is applied again.
+    fingerprint: String,
+    /// Runs skipped since the server was last contacted.
+    skipped_runs: u32,
+    /// Time the server was last contacted, as a Unix timestamp.
+    reconciled_at: i64,
+}
+
+/// Path of the state file of the configuration.
+pub fn path(config_name: &str, options: Option<&StateOptions>) -> PathBuf {
+    options
+        .and_then(|options| options.path.as_ref())
+        .map_or_else(
+            || CONFIG_DETECTION_PATH.join(format!("{config_name}.state.json")),
+            PathBuf::from,
+        )
+}
+
+impl State {
+    /// Read the state file, a missing or unreadable file is an empty state.
+    pub async fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .await
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Write the state file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent).await?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?).await?;
+        fs::rename(&tmp_path, path).await?;
+        Ok(())
+    }
+
+    /// Whether the server can be skipped, because the addresses and the
+    /// server were applied before and no reconcile is due. A skip is counted
+    /// towards the next reconcile.
+    pub fn skip(
+        &mut self,
+        server: &Server,
+        ipv4: &str,
+        ipv6: &str,
+        options: &StateOptions,
+        now: i64,
+    ) -> bool {
+        let Some(state) = self.servers.get_mut(&server.name) else {
+            return false;
+        };
+        let is_reconcile_due = options
+            .reconcile_runs
+            .is_some_and(|runs| state.skipped_runs >= runs)
+            || options.reconcile_hours.is_some_and(|hours| {
+                now - state.reconciled_at >= i64::from(hours) * 3600
+            });
+        if is_reconcile_due
+            || state.ipv4 != ipv4
+            || state.ipv6 != ipv6
+            || state.fingerprint != fingerprint(server)
+        {
+            return false;
+        }
+        state.skipped_runs += 1;
+        true
+    }
+
+    /// Mark the current addresses as seen in this run.
+    pub fn see(&mut self, current: &[&str], now: i64) {
+        for address in current.iter().rev().filter(|ip| !ip.is_empty()) {
+            self.addresses.retain(|seen| seen.address != *address);
+            self.addresses.insert(
+                0,
+                SeenAddress {
+                    address: (*address).to_string(),
+                    last_seen: now,
+                },
+            );
+        }
+    }
+
+    /// Forget the addresses that are neither among the `recent` most
+    /// recently seen ones of their IP version, nor seen since the given time.
+    pub fn forget(&mut self, recent: usize, since: Option<i64>) {
+        let mut counts = [0, 0];
+        self.addresses.retain(|seen| {
+            let count = &mut counts[usize::from(seen.address.contains(':'))];
+            *count += 1;
+            *count <= recent
+                || since.is_some_and(|since| seen.last_seen >= since)
+        });
+    }
+
+    /// The seen addresses other than the current ones, the most recently
+    /// seen first.
+    #[must_use]
+    pub fn previous(&self, current: &[&str]) -> Vec<String> {
+        self.addresses
+            .iter()
+            .filter(|seen| !current.contains(&seen.address.as_str()))
+            .map(|seen| seen.address.clone())
+            .collect()
+    }
+
+    /// Give the previous addresses a slot each, as long as slots are free.
+    ///
+    /// An address keeps its slot until it is forgotten, so that only the
+    /// evicted sibling rule changes when the address changes.
+    pub fn assign_slots(&mut self, previous: &[String], count: usize) -> Slots {
+        for (slots, is_ipv6) in
+            [(&mut self.slots.ipv4, false), (&mut self.slots.ipv6, true)]
+        {
+            let kept: Vec<&String> = previous
+                .iter()
+                .filter(|address| address.contains(':') == is_ipv6)
+                .collect();
+            slots.resize(count, String::new());
+            for slot in slots.iter_mut() {
+                if !kept.contains(&&*slot) {
+                    slot.clear();
+                }
+            }
+            for address in kept {
+                if !slots.contains(address)
+                    && let Some(free) =
+                        slots.iter_mut().find(|slot| slot.is_empty())
+                {
+                    free.clone_from(address);
+                }
+            }
+        }
+        self.slots.clone()
+    }
+
+    /// The addresses reported by the member, empty if it never reported.
+    pub fn member_mut(&mut self, name: &str) -> &mut MemberState {
+        self.members.entry(name.to_string()).or_default()
+    }
+
+    /// Forget the addresses of the member last reported before `before`,
+    /// and return what is left.
+    pub fn expire_member(&mut self, name: &str, before: i64) -> MemberState {
+        let member = self.member_mut(name);
+        member.expire(before);
+        member.clone()
+    }
+
+    /// Record an address reported by the member at `now`, and forget its
+    /// addresses last reported before `expire_before`. Its addresses count
+    /// as not applied if they changed. Returns the addresses of the member
+    /// and whether they changed.
+    pub fn report_member(
+        &mut self,
+        name: &str,
+        address: &str,
+        now: i64,
+        expire_before: i64,
+    ) -> (MemberState, bool) {
+        let member = self.member_mut(name);
+        let (current, reported_at) = if address.contains(':') {
+            (&mut member.ipv6, &mut member.ipv6_reported_at)
+        } else {
+            (&mut member.ipv4, &mut member.ipv4_reported_at)
+        };
+        *reported_at = now;
+        let mut changed = current != address;
+        if changed {
+            *current = address.to_string();
+            member.is_applied = false;
+        }
+        changed |= member.expire(expire_before);
+        (member.clone(), changed)
+    }
+
+    /// Names of the members that reported an address.
+    #[must_use]
+    pub fn member_names(&self) -> Vec<String> {
+        self.members.keys().cloned().collect()
+    }
+
+    /// Forget the members that are not among the given ones.
+    pub fn retain_members(&mut self, names: &[&str]) {
+        self.members
+            .retain(|name, _| names.contains(&name.as_str()));
+    }
+
+    /// Record the addresses applied to the server, or forget the server if
+    /// any of its targets failed, so that it is contacted on the next run.
+    pub fn record(
+        &mut self,
+        server: &Server,
+        ipv4: &str,
+        ipv6: &str,
+        now: i64,
+        is_applied: bool,
+    ) {
+        if !is_applied {
+            self.servers.remove(&server.name);
+            return;
+        }
+        self.servers.insert(
+            server.name.clone(),
+            ServerState {
+                ipv4: ipv4.to_string(),
+                ipv6: ipv6.to_string(),
+                fingerprint: fingerprint(server),
+                skipped_runs: 0,
+                reconciled_at: now,
+            },
+        );
+    }
+}
+
+/// SHA-256 of the server definition with its keys sorted, so the value
+/// written to disk is the same across builds of solo.
+fn fingerprint(server: &Server) -> String {
+    let definition = serde_json::to_value(server)
+        .map(|value| value.to_string())
+        .unwrap_or_default();
+    hex::encode(Sha256::digest(definition))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One run of `solo go` fetching `current`, as done in `exec`, the state
+    /// read back from its file in between.
+    fn run(
+        state: &State,
+        current: &str,
+        now: i64,
+        size: usize,
+        grace_seconds: Option<i64>,
+    ) -> (State, Vec<String>, Slots) {
+        let mut state: State =
+            serde_json::from_str(&serde_json::to_string(state).unwrap())
+                .unwrap();
+        let current = [current, ""];
+        state.see(&current, now);
+        state.forget(size, grace_seconds.map(|seconds| now - seconds));
+        let previous = state.previous(&current);
+        let slots = state.assign_slots(&previous, size.saturating_sub(1));
+        (state, previous, slots)
+    }
+
+    #[test]
+    fn grace_period_expires() {
+        let (state, previous, _) =
+            run(&State::default(), "1.1.1.1", 0, 0, Some(100));
+        assert!(previous.is_empty());
+        let (state, previous, _) = run(&state, "2.2.2.2", 50, 0, Some(100));
+        assert_eq!(previous, ["1.1.1.1"]);
+        let (state, previous, _) = run(&state, "::1", 100, 0, Some(100));
+        assert_eq!(previous, ["2.2.2.2", "1.1.1.1"]);
+        let (_, previous, _) = run(&state, "::1", 101, 0, Some(100));
+        assert_eq!(previous, ["2.2.2.2"]);
+    }
+
+    #[test]
+    fn history_keeps_size_addresses_per_version() {
+        let mut state = State::default();
+        let addresses = ["1.1.1.1", "::1", "2.2.2.2", "::2", "3.3.3.3"];
+        for (now, address) in (0..).zip(addresses) {
+            state = run(&state, address, now, 2, None).0;
+        }
+        assert_eq!(state.previous(&["3.3.3.3"]), ["::2", "2.2.2.2", "::1"]);
+    }
+
+    #[test]
+    fn slots_are_reused_and_stable_across_runs() {
+        let (state, _, slots) = run(&State::default(), "1.1.1.1", 0, 3, None);
+        assert_eq!(slots.ipv4, ["", ""]);
+        let (state, _, slots) = run(&state, "2.2.2.2", 1, 3, None);
+        assert_eq!(slots.ipv4, ["1.1.1.1", ""]);
+        let (state, _, slots) = run(&state, "3.3.3.3", 2, 3, None);
+        assert_eq!(slots.ipv4, ["1.1.1.1", "2.2.2.2"]);
+        // The evicted address frees its slot, the others keep theirs
+        let (state, _, slots) = run(&state, "4.4.4.4", 3, 3, None);
+        assert_eq!(slots.ipv4, ["3.3.3.3", "2.2.2.2"]);
+        let (_, _, slots) = run(&state, "4.4.4.4", 4, 3, None);
+        assert_eq!(slots.ipv4, ["3.3.3.3", "2.2.2.2"]);
+        assert!(slots.ipv6.iter().all(String::is_empty));
+    }
+
+    #[test]
+    fn member_report_changes_once() {
+        let mut state = State::default();
+        let (member, changed) = state.report_member("alice", "1.1.1.1", 0, 0);
+        assert!(changed && !member.is_applied);
+        state.member_mut("alice").is_applied = true;
+        let (member, changed) = state.report_member("alice", "1.1.1.1", 1, 0);
+        assert!(!changed && member.is_applied);
+        let (member, changed) = state.report_member("alice", "::1", 2, 0);
+        assert!(changed && !member.is_applied);
+        assert_eq!(member.ipv4, "1.1.1.1");
+    }
+
+    #[test]
+    fn member_address_expires() {
+        let mut state = State::default();
+        state.report_member("alice", "1.1.1.1", 0, 0);
+        state.report_member("alice", "::1", 50, 0);
+        state.member_mut("alice").is_applied = true;
+
+        // Both addresses are still reported
+        let (member, changed) = state.report_member("alice", "::1", 100, 0);
+        assert!(!changed && member.is_applied);
+        assert_eq!(member.ipv4, "1.1.1.1");
+
+        // The member moved to an IPv6-only network
+        let (member, changed) = state.report_member("alice", "::1", 200, 100);
+        assert!(changed && !member.is_applied);
+        assert_eq!((member.ipv4.as_str(), member.ipv6.as_str()), ("", "::1"));
+    }
+}
@@ -0,0 +1,93 @@
+use std::process::exit;
+
+use anyhow::{Result, anyhow};
+use cnxt::Colorize as _;
+use rust_i18n::t;
+use solo_lib::client;
+
+use super::{ErrorResponse, REPORT_PATH, ReportRequest, ReportResponse};
+
+/// Report the address of this member to a solo server.
+pub async fn report(server_url: String, ip: Option<String>, token: String) {
+    let endpoint = format!("{}{REPORT_PATH}", server_url.trim_end_matches('/'));
+    println!(
+        "{}",
+        t!("Reporting to %{url}", url = endpoint).bright_cyan()
+    );
+
+    let response = match send(&endpoint, ip, &token).await {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!(
+                "{}",
+                t!("Failed to report: %{error}", error = e).bright_red()
+            );
+            exit(1);
+        }
+    };
+
+    println!(
+        "{}",
+        t!(
+            "Reported %{address} as %{member}",
+            address = response.ip,
+            member = response.member
+        )
+        .bright_green()
+    );
+    if !response.changed {
+        println!("{}", t!("Address unchanged, nothing to do").bright_green());
+    }
+
+    let mut is_failed = false;
+    for server in response.servers {
+        match server.error {
+            Some(message) => {
+                is_failed = true;
+                println!(
+                    "[{}] {}",
+                    server.name.bright_red(),
+                    message.bright_red()
+                );
+            }
+            None if server.changed => println!(
+                "[{}] {}",
+                server.name.bright_green(),
+                t!("IP changed successfully").bright_green()
+            ),
+            None => println!(
+                "[{}] {}",
+                server.name.bright_green(),
+                t!("IP unchanged").bright_green()
+            ),
+        }
+    }
+    if is_failed {
+        exit(1);
+    }
+}
+
+async fn send(
+    endpoint: &str,
+    ip: Option<String>,
+    token: &str,
+) -> Result<ReportResponse> {
+    let client = client::new_builder().build()?;
+    let response = client
+        .post(endpoint)
+        .bearer_auth(token)
+        .json(&ReportRequest { ip })
+        .send()
+        .await?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(response.json().await?)
+    } else {
+        let error = response
+            .json::<ErrorResponse>()
+            .await
+            .map_or_else(|_| status.to_string(), |response| response.error);
+        Err(anyhow!(error))
+    }
+}
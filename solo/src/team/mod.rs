@@ -0,0 +1,45 @@
+//! Team mode: members report their addresses to a central solo, which keeps
+//! one rule per member in sync, so that cloud credentials stay on a single
+//! machine.
+
+use serde::{Deserialize, Serialize};
+
+mod client;
+mod server;
+
+pub use client::report;
+pub use server::serve;
+
+/// Path members report to.
+const REPORT_PATH: &str = "/report";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ReportRequest {
+    /// Address to allow, the source address of the request is used if not
+    /// set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ip: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ReportResponse {
+    member: String,
+    ip: String,
+    /// Whether the address differs from the one applied before, the servers
+    /// are not contacted otherwise.
+    changed: bool,
+    servers: Vec<ServerResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ServerResult {
+    name: String,
+    changed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ErrorResponse {
+    error: String,
+}
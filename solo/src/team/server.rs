@@ -0,0 +1,511 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    convert::Infallible,
+    iter,
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    process::exit,
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::Local;
+use cnxt::Colorize as _;
+use http_body_util::{BodyExt as _, Full, Limited};
+use hyper::{
+    HeaderMap, Method, Request, Response, StatusCode,
+    body::{Bytes, Incoming},
+    header,
+    server::conn::http1,
+    service::service_fn,
+};
+use hyper_util::rt::{TokioIo, TokioTimer};
+use reqwest::Client;
+use rust_i18n::t;
+use serde::Serialize;
+use solo_lib::client;
+use tokio::{
+    net::TcpListener,
+    sync::{
+        Mutex,
+        mpsc::{self, Sender},
+    },
+};
+
+use super::{
+    ErrorResponse, REPORT_PATH, ReportRequest, ReportResponse, ServerResult,
+};
+use crate::{
+    config::{
+        definition::{
+            Config, MACHINE_TYPES_WITH_SINGLE_ADDRESS_RULES, Schedule,
+            TeamMember, TeamOptions,
+        },
+        reader::process_config,
+    },
+    exec::{ThreadStep, numbered_siblings},
+    ipfetcher::{Protocol, is_public},
+    notification::send_notification,
+    report::{
+        ExecutionReport, ExecutionReportIpFetching,
+        ExecutionReportServerStatus, show_brief_report,
+    },
+    sdk::execute_server_task,
+    state::{self, MemberState, State},
+};
+
+/// Largest accepted request body.
+const MAX_BODY_SIZE: usize = 4096;
+
+/// Time given to a client to send the request headers.
+const HEADER_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Hours after which an address that is no longer reported is forgotten.
+const DEFAULT_EXPIRE_HOURS: u32 = 24;
+
+struct Team {
+    config: Config,
+    options: TeamOptions,
+    client: Client,
+    tx: Sender<ThreadStep>,
+    /// Reported addresses of the members, kept in the state file so that
+    /// they survive a restart. The lock is only held to read or record them.
+    state: Mutex<State>,
+    state_path: PathBuf,
+    /// Held while the servers are updated for a member, so that the reports
+    /// of a member are applied one after another.
+    applying: HashMap<String, Mutex<()>>,
+}
+
+type HandlerResult<T> = Result<T, (StatusCode, Cow<'static, str>)>;
+
+/// Listen for reports of team members and apply them to the servers.
+pub async fn serve(config_name: String) {
+    let config = process_config(vec![config_name]).unwrap().remove(0);
+    let Some(options) = config.team.clone() else {
+        eprintln!(
+            "{}",
+            t!(
+                "Configuration %{name} has no team section",
+                name = config.name
+            )
+            .bright_red()
+        );
+        exit(1);
+    };
+
+    let listener = match TcpListener::bind(&options.listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!(
+                "{}",
+                t!(
+                    "Unable to listen on %{address}: %{error}",
+                    address = options.listen,
+                    error = e
+                )
+                .bright_red()
+            );
+            exit(1);
+        }
+    };
+    println!(
+        "{}",
+        t!(
+            "Listening on %{address}, members report to %{path}",
+            address = options.listen,
+            path = REPORT_PATH
+        )
+        .bright_green()
+    );
+
+    let (tx, mut rx) = mpsc::channel::<ThreadStep>(100);
+    tokio::spawn(async move {
+        while let Some(step) = rx.recv().await {
+            if let Some(name) = step.name {
+                println!(
+                    "[{}] {}",
+                    name.bright_magenta(),
+                    step.msg.bright_cyan()
+                );
+            } else {
+                println!("{}", step.msg.bright_cyan());
+            }
+        }
+    });
+
+    let mut builder = client::new_builder();
+    if config.no_proxy {
+        builder = builder.no_proxy();
+    }
+    let state_path = state::path(&config.name, config.state.as_ref());
+    let mut state = State::load(&state_path).await;
+    let names: Vec<&str> =
+        options.members.iter().map(|m| m.name.as_str()).collect();
+    state.retain_members(&names);
+    let applying = names
+        .iter()
+        .map(|name| ((*name).to_string(), Mutex::new(())))
+        .collect();
+    let team = Arc::new(Team {
+        client: builder.build().unwrap(),
+        config,
+        options,
+        tx,
+        state: Mutex::new(state),
+        state_path,
+        applying,
+    });
+
+    // Drift is corrected by applying the known addresses again
+    if let Schedule::Loop(interval) = team.config.schedule {
+        let team = team.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(Duration::from_secs(interval as u64)).await;
+                reapply(&team).await;
+            }
+        });
+    }
+
+    loop {
+        let Ok((stream, remote)) = listener.accept().await else {
+            continue;
+        };
+        let team = team.clone();
+        tokio::spawn(async move {
+            let service = service_fn(move |request| {
+                handle(team.clone(), remote, request)
+            });
+            let _ = http1::Builder::new()
+                .timer(TokioTimer::new())
+                .header_read_timeout(HEADER_READ_TIMEOUT)
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+    }
+}
+
+async fn handle(
+    team: Arc<Team>,
+    remote: SocketAddr,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    Ok(match respond(&team, remote, request).await {
+        Ok(response) => json_response(StatusCode::OK, &response),
+        Err((status, error)) => json_response(
+            status,
+            &ErrorResponse {
+                error: error.into_owned(),
+            },
+        ),
+    })
+}
+
+async fn respond(
+    team: &Team,
+    remote: SocketAddr,
+    request: Request<Incoming>,
+) -> HandlerResult<ReportResponse> {
+    if request.uri().path() != REPORT_PATH {
+        return Err((StatusCode::NOT_FOUND, t!("Not found")));
+    }
+    if request.method() != Method::POST {
+        return Err((StatusCode::METHOD_NOT_ALLOWED, t!("Method not allowed")));
+    }
+
+    let member = authenticate(&team.options.members, request.headers())
+        .ok_or_else(|| (StatusCode::UNAUTHORIZED, t!("Invalid token")))?;
+    let source = source_address(&team.options, remote, request.headers())?;
+
+    let body = Limited::new(request.into_body(), MAX_BODY_SIZE)
+        .collect()
+        .await
+        .map_err(|_| (StatusCode::BAD_REQUEST, t!("Invalid request body")))?
+        .to_bytes();
+    let body: ReportRequest = if body.is_empty() {
+        ReportRequest::default()
+    } else {
+        serde_json::from_slice(&body).map_err(|_| {
+            (StatusCode::BAD_REQUEST, t!("Invalid request body"))
+        })?
+    };
+
+    let reported = body.ip.unwrap_or(source);
+    let ip = reported
+        .parse::<IpAddr>()
+        .map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                t!("Invalid IP address %{address}", address = reported),
+            )
+        })?
+        .to_canonical();
+    if !team.config.allow_private_ip && !is_public(ip) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            t!(
+                "Address %{address} is not public, set allow_private_ip to \
+                 accept it",
+                address = ip
+            ),
+        ));
+    }
+
+    Ok(apply(team, &member.name, ip).await)
+}
+
+/// Find the member owning the bearer token of the request.
+fn authenticate<'a>(
+    members: &'a [TeamMember],
+    headers: &HeaderMap,
+) -> Option<&'a TeamMember> {
+    let token = headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")?;
+    members
+        .iter()
+        .find(|member| tokens_match(&member.token, token))
+}
+
+/// Compare tokens in constant time, so that they cannot be guessed byte by
+/// byte.
+fn tokens_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// The address the request comes from, read from the configured header if
+/// solo runs behind a reverse proxy.
+fn source_address(
+    options: &TeamOptions,
+    remote: SocketAddr,
+    headers: &HeaderMap,
+) -> HandlerResult<String> {
+    let Some(name) = &options.address_header else {
+        return Ok(remote.ip().to_string());
+    };
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                t!("No address in header %{header}", header = name),
+            )
+        })
+}
+
+/// Record the address of the member and update the servers if it changed.
+async fn apply(team: &Team, member: &str, ip: IpAddr) -> ReportResponse {
+    let _applying = team.applying[member].lock().await;
+    let address = ip.to_string();
+    let now = Local::now().timestamp();
+    let (snapshot, changed) = team.state.lock().await.report_member(
+        member,
+        &address,
+        now,
+        expire_before(team, now),
+    );
+    if snapshot.is_applied {
+        return ReportResponse {
+            member: member.to_string(),
+            ip: address,
+            changed,
+            servers: Vec::new(),
+        };
+    }
+
+    let report = apply_member(team, member, &snapshot).await;
+    record(team, member, &report).await;
+    let servers = report
+        .server_status
+        .iter()
+        .map(|server| match &server.status {
+            ExecutionReportServerStatus::Success { is_ip_changed, .. } => {
+                ServerResult {
+                    name: server.name.to_string(),
+                    changed: *is_ip_changed,
+                    error: None,
+                }
+            }
+            ExecutionReportServerStatus::Failed { error, when: step } => {
+                ServerResult {
+                    name: server.name.to_string(),
+                    changed: false,
+                    error: Some(format!(
+                        "{} | {error}",
+                        t!("Error occurred at %{when}", when = step)
+                    )),
+                }
+            }
+        })
+        .collect();
+    finish(team, report);
+
+    ReportResponse {
+        member: member.to_string(),
+        ip: address,
+        changed,
+        servers,
+    }
+}
+
+/// Addresses of members last reported before this time are forgotten.
+fn expire_before(team: &Team, now: i64) -> i64 {
+    let hours = team.options.expire_hours.unwrap_or(DEFAULT_EXPIRE_HOURS);
+    now - i64::from(hours) * 3600
+}
+
+/// Apply the known addresses of every member again.
+async fn reapply(team: &Team) {
+    let names = team.state.lock().await.member_names();
+    for member in names {
+        let _applying = team.applying[&member].lock().await;
+        let expire_before = expire_before(team, Local::now().timestamp());
+        let snapshot = team
+            .state
+            .lock()
+            .await
+            .expire_member(&member, expire_before);
+        let report = apply_member(team, &member, &snapshot).await;
+        record(team, &member, &report).await;
+        finish(team, report);
+    }
+}
+
+/// Record whether the addresses of the member were applied and save the
+/// state file.
+async fn record(team: &Team, member: &str, report: &ExecutionReport<'_>) {
+    let mut state = team.state.lock().await;
+    state.member_mut(member).is_applied = is_applied(report);
+    if let Err(e) = state.save(&team.state_path).await {
+        let _ = team.tx.try_send(ThreadStep {
+            name: None,
+            msg: t!("Unable to save the state file: %{error}", error = e),
+        });
+    }
+}
+
+/// Update the rules of the member on every server.
+async fn apply_member(
+    team: &Team,
+    member: &str,
+    state: &MemberState,
+) -> ExecutionReport<'static> {
+    let mut server_status = Vec::new();
+    for server in &team.config.servers {
+        // A member may not have reported an address of every family
+        let protocol = match (
+            server.protocol,
+            state.ipv4.is_empty(),
+            state.ipv6.is_empty(),
+        ) {
+            (Protocol::Both, false, false) => Protocol::Both,
+            (Protocol::V4 | Protocol::Both, false, _) => Protocol::V4,
+            (Protocol::V6 | Protocol::Both, _, false) => Protocol::V6,
+            _ => continue,
+        };
+        let mut server = server.clone();
+        server.protocol = protocol;
+        server.rules = server
+            .rules
+            .iter()
+            .map(|rule| format!("{rule}-{member}"))
+            .collect();
+        server
+            .extra_addresses
+            .splice(0..0, team.config.extra_addresses.iter().cloned());
+
+        // Rules holding a single address keep the extra addresses in
+        // siblings
+        let siblings = if MACHINE_TYPES_WITH_SINGLE_ADDRESS_RULES
+            .contains(&server.machine_type)
+        {
+            numbered_siblings(
+                &server,
+                "-extra-",
+                &server.extra_addresses,
+                0,
+                &state.ipv4,
+                &state.ipv6,
+            )
+        } else {
+            Vec::new()
+        };
+        let current = (
+            server,
+            Cow::Owned(state.ipv4.clone()),
+            Cow::Owned(state.ipv6.clone()),
+        );
+        for (server, ipv4, ipv6) in iter::once(current).chain(siblings) {
+            let result = execute_server_task(
+                team.tx.clone(),
+                &team.client,
+                server,
+                ipv4,
+                ipv6,
+            )
+            .await;
+            server_status.extend(result);
+        }
+    }
+
+    ExecutionReport {
+        id: 0,
+        config_name: Cow::Owned(format!("{} / {member}", team.config.name)),
+        finished_timestamp: Local::now().timestamp(),
+        ip_fetching_status: ExecutionReportIpFetching::Success {
+            ipv4: Cow::Owned(state.ipv4.clone()),
+            ipv6: Cow::Owned(state.ipv6.clone()),
+            additional: Vec::new(),
+            warnings: Vec::new(),
+        },
+        server_status,
+    }
+}
+
+fn is_applied(report: &ExecutionReport) -> bool {
+    report.server_status.iter().all(|server| {
+        matches!(server.status, ExecutionReportServerStatus::Success { .. })
+    })
+}
+
+/// Show the report and send the notifications.
+fn finish(team: &Team, report: ExecutionReport<'static>) {
+    println!("{}", report.config_name.bright_yellow());
+    for line in show_brief_report(&report, true) {
+        println!("{line}");
+    }
+
+    let notifications = team.config.notifications.clone();
+    tokio::task::spawn_blocking(move || {
+        tokio::runtime::Handle::current().block_on(async {
+            send_notification(&notifications, report, 1, 0).await;
+        });
+    });
+}
+
+fn json_response(
+    status: StatusCode,
+    body: &impl Serialize,
+) -> Response<Full<Bytes>> {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    response
+}